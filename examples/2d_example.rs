@@ -1,9 +1,19 @@
 use nalgebra_glm::{Vec2, Vec3, Vec4};
 
-use fiji::{Background, Border, Circle, Context, KeyCode, MouseButton, Polygon, Rect, Text};
+use fiji::{
+    Anchor, Background, Border, BorderAlignment, Camera2D, Circle, Context, KeyCode, Line, LineCap,
+    LineJoin, MouseButton, Path, PathStroke, PointShape, Points, Polygon, Polyline, Rect,
+    StrokeStyle, Text,
+};
+use rand::Rng;
 
 fn main() {
     let mut context = Context::new(1280, 720, "Fiji 2D Example");
+    let screen_center = Vec2::new(640., 360.);
+    context.camera_2d = Camera2D::new_default()
+        .with_origin(screen_center)
+        .with_position(screen_center)
+        .with_deadzone(Vec2::new(400., 250.));
 
     let mut pos = Vec2::new(100., 100.);
     let mut border_width = 0;
@@ -11,6 +21,19 @@ fn main() {
     let mut polygon_points = Vec::new();
     let mut timer = 0.;
 
+    // Thousands of stars in a single draw
+    let mut rng = rand::thread_rng();
+    let star_positions = (0..5000)
+        .map(|_| Vec2::new(rng.gen_range(-2000.0..3000.), rng.gen_range(-2000.0..3000.)))
+        .collect::<Vec<_>>();
+    let star_colors = (0..5000)
+        .map(|_| Vec4::new(1., 1., rng.gen_range(0.5..1.), rng.gen_range(0.3..1.)))
+        .collect::<Vec<_>>();
+    let stars = Points::new_with_positions(star_positions)
+        .with_colors(star_colors)
+        .with_size(3.)
+        .with_shape(PointShape::Round);
+
     context.run(move |input, fiji_event_handler, context| {
         if input.key_pressed(&KeyCode::Escape) {
             fiji_event_handler.exit();
@@ -26,6 +49,12 @@ fn main() {
             border_width += 1;
         }
 
+        // Hard edges, like in pixel art
+        if input.key_pressed(&KeyCode::P) {
+            let antialiasing = context.antialiasing();
+            context.set_antialiasing(!antialiasing);
+        }
+
         if input.key_held(&KeyCode::A) {
             pos += Vec2::new(-1., 0.);
         }
@@ -39,8 +68,25 @@ fn main() {
             pos += Vec2::new(0., 1.);
         }
 
+        context.camera_2d.follow(pos, 5.);
+        if input.key_pressed(&KeyCode::Space) {
+            context.camera_2d.shake(15.);
+        }
+
+        // Zoom towards the mouse cursor
+        if input.key_held(&KeyCode::R) {
+            let zoom = context.camera_2d.zoom * 1.02;
+            context.camera_2d.zoom_at(*input.mouse_position(), zoom);
+        }
+        if input.key_held(&KeyCode::F) {
+            let zoom = context.camera_2d.zoom / 1.02;
+            context.camera_2d.zoom_at(*input.mouse_position(), zoom);
+        }
+
+        let mouse_world = context.camera_2d.screen_to_world(*input.mouse_position());
+
         if input.mouse_button_pressed(&MouseButton::Left) {
-            polygon_points.push(input.mouse_position().clone());
+            polygon_points.push(mouse_world);
         }
         if input.key_pressed(&KeyCode::X) {
             if polygon_points.len() > 0 {
@@ -53,16 +99,35 @@ fn main() {
         timer += 0.03;
 
         context.background(Background::new_with_color(Vec3::new(0., 0., 0.)));
+        context.points(stars.clone());
 
         context.ui_text(Text::new_with_text(&format!("{:.2}", timer)));
 
         context.rect(
             Rect::new_default()
                 .with_color(Vec4::new(0., 0.5, 0.5, 1.))
-                .with_position(input.mouse_position().clone())
+                .with_position(mouse_world)
                 .with_size(Vec2::new(50., 50.)),
         );
 
+        // Selection marquee with marching ants
+        context.rect(
+            Rect::new_default()
+                .with_color(Vec4::new(0., 0., 0., 0.))
+                .with_position(mouse_world)
+                .with_size(Vec2::new(70., 70.))
+                .with_border(Border::new(Vec4::new(1., 1., 1., 1.), 2).with_style(
+                    StrokeStyle::new_dashed(vec![6., 4.]).with_dash_offset(timer * 20.),
+                )),
+        );
+
+        context.line(
+            Line::new_with_points(Vec2::new(100., 680.), Vec2::new(600., 680.))
+                .with_color(Vec4::new(0.9, 0.9, 0.3, 1.))
+                .with_thickness(6)
+                .with_style(StrokeStyle::new_dotted(14.)),
+        );
+
         context.circle(
             Circle::new_default()
                 .with_color(Vec4::new(1., 0., 1., 0.2))
@@ -78,6 +143,46 @@ fn main() {
                 .with_size(Vec2::new(20., 50.)),
         );
 
+        context.rect(
+            Rect::new_default()
+                .with_color(Vec4::new(0.2, 0.6, 1., 1.))
+                .with_position(Vec2::new(1000., 150.))
+                .with_size(Vec2::new(120., 60.))
+                .with_rotation(timer)
+                .with_corner_radius(15.)
+                .with_anchor(Anchor::TopLeft)
+                .with_border(Border::new(Vec4::new(1., 1., 1., 1.), border_width)),
+        );
+
+        // A small solar system, every push/pop nests the next body in the previous one
+        context.push();
+        context.translate(Vec2::new(1000., 500.));
+        context.rotate(timer * 0.5);
+        context.circle(
+            Circle::new_default()
+                .with_color(Vec4::new(1., 0.8, 0.2, 1.))
+                .with_position(Vec2::new(0., 0.))
+                .with_radius(30.),
+        );
+        context.push();
+        context.translate(Vec2::new(120., 0.));
+        context.rotate(timer * 2.);
+        context.scale(Vec2::new(0.5, 0.5));
+        context.rect(
+            Rect::new_default()
+                .with_color(Vec4::new(0.2, 0.5, 1., 1.))
+                .with_position(Vec2::new(0., 0.))
+                .with_size(Vec2::new(40., 40.)),
+        );
+        context.circle(
+            Circle::new_default()
+                .with_color(Vec4::new(0.8, 0.8, 0.8, 1.))
+                .with_position(Vec2::new(70., 0.))
+                .with_radius(10.),
+        );
+        context.pop();
+        context.pop();
+
         context.rect(
             Rect::new_default()
                 .with_color(Vec4::new(1., 1., 0., 1.))
@@ -86,10 +191,56 @@ fn main() {
                 .with_border(Border::new(Vec4::new(1., 1., 1., 1.), border_width)),
         );
 
+        context.path(
+            Path::new()
+                .move_to(Vec2::new(150., 500.))
+                .cubic_to(
+                    Vec2::new(150., 440.),
+                    Vec2::new(250., 440.),
+                    Vec2::new(250., 500.),
+                )
+                .cubic_to(
+                    Vec2::new(250., 560.),
+                    Vec2::new(150., 600.),
+                    Vec2::new(150., 640.),
+                )
+                .cubic_to(
+                    Vec2::new(150., 600.),
+                    Vec2::new(50., 560.),
+                    Vec2::new(50., 500.),
+                )
+                .cubic_to(
+                    Vec2::new(50., 440.),
+                    Vec2::new(150., 440.),
+                    Vec2::new(150., 500.),
+                )
+                .close()
+                .with_fill(Vec4::new(0.8, 0.1, 0.2, 1.))
+                .with_stroke(PathStroke::new(Vec4::new(1., 1., 1., 1.), 3.).with_style(
+                    StrokeStyle::new_dashed(vec![12., 6.]).with_dash_offset(timer * 20.),
+                )),
+        );
+
         if polygon_points.len() >= 3 {
             context.polygon(
                 Polygon::new_with_points(polygon_points.clone())
-                    .with_color(Vec4::new(0.9, 0.3, 0.5, 1.)),
+                    .with_color(Vec4::new(0.9, 0.3, 0.5, 1.))
+                    .with_border(
+                        Border::new(Vec4::new(0.3, 0.1, 0.2, 1.), 6)
+                            .with_alignment(BorderAlignment::Inner)
+                            .with_join(LineJoin::Round)
+                            .with_style(StrokeStyle::new_dashed(vec![20., 8.])),
+                    ),
+            );
+        }
+
+        if polygon_points.len() >= 2 {
+            context.polyline(
+                Polyline::new_with_points(polygon_points.clone())
+                    .with_color(Vec4::new(1., 1., 1., 1.))
+                    .with_thickness(4.)
+                    .with_join(LineJoin::Round)
+                    .with_cap(LineCap::Round),
             );
         }
 
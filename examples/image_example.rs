@@ -1,18 +1,148 @@
-use fiji::{Context, Figure, FigureImage, KeyCode};
-use nalgebra_glm::UVec2;
+use fiji::{
+    Animation, AnimationEvent, AnimationMode, Context, Figure, FigureImage, FigureImageFormat,
+    FilterMode, KeyCode, SourceRect, SpriteSheet, TextureAtlas, WrapMode,
+};
+use nalgebra_glm::{UVec2, Vec2, Vec4};
+
+const LIFE_SIZE: u32 = 64;
+const ALIVE: [u8; 4] = [255, 255, 255, 255];
+const DEAD: [u8; 4] = [0, 0, 0, 255];
 
 pub fn main() {
     let mut context = Context::new(1280, 720, "Fiji Image Example");
 
     let figure = Figure::new_with_image(FigureImage::new_with_size(UVec2::new(10, 10)));
 
+    let texture = context
+        .load_texture("assets/images/sprite_sheet.png")
+        .unwrap();
+    let sheet = SpriteSheet::from_grid(texture.image().clone(), UVec2::new(16, 16));
+    let mut walk = Animation::from_range(0..sheet.len(), 8.).with_event(2, "step");
+    let mut bounce = Animation::from_range(0..sheet.len(), 4.).with_mode(AnimationMode::PingPong);
+    let mut flip = false;
+
+    // Small images packed into one atlas page are drawn with a single draw
+    let mut atlas = TextureAtlas::new_default();
+    let icons = (0..16)
+        .map(|i| atlas.add(&i.to_string(), &create_icon(i)))
+        .collect::<Vec<_>>();
+
+    // Only the cells that change are uploaded every frame
+    let mut life = FigureImage::new_with_size(UVec2::new(LIFE_SIZE, LIFE_SIZE));
+    life.fill_rect(UVec2::new(30, 30), UVec2::new(3, 1), ALIVE);
+    life.set_pixel(32, 29, ALIVE);
+    life.set_pixel(31, 28, ALIVE);
+
     context.run(move |input, event_handler, context| {
         if input.key_pressed(&KeyCode::Escape) {
             event_handler.exit();
         }
 
+        for event in walk.update(context.dt()) {
+            if event == AnimationEvent::Frame("step".to_string()) {
+                flip = !flip;
+            }
+        }
+        bounce.update(context.dt());
+
         context.figure(figure.clone());
 
+        step_life(&mut life);
+        context.figure(
+            Figure::new_with_image(life.clone())
+                .with_position(Vec2::new(1000., 500.))
+                .with_size(Vec2::new(256., 256.)),
+        );
+
+        context.figure(
+            sheet
+                .figure(walk.current_frame())
+                .with_position(Vec2::new(300., 300.))
+                .with_size(Vec2::new(128., 128.))
+                .with_flip_x(flip),
+        );
+
+        context.figure(
+            sheet
+                .figure(bounce.current_frame())
+                .with_position(Vec2::new(600., 300.))
+                .with_size(Vec2::new(128., 128.))
+                .with_flip_y(true),
+        );
+
+        for (i, icon) in icons.iter().cycle().take(200).enumerate() {
+            context.figure(atlas.figure(*icon).with_position(Vec2::new(
+                20. + (i % 40) as f32 * 20.,
+                20. + (i / 40) as f32 * 20.,
+            )));
+        }
+
+        // Loading the same file again reuses the uploaded image
+        let texture = context
+            .load_texture("assets/images/sprite_sheet.png")
+            .unwrap();
+        context.figure(
+            Figure::new_with_texture(&texture)
+                .with_position(Vec2::new(450., 500.))
+                .with_size(Vec2::new(256., 64.)),
+        );
+
+        // Smoothly scaled down, tinted and half transparent
+        context.figure(
+            Figure::new_with_texture(&texture)
+                .with_position(Vec2::new(450., 600.))
+                .with_size(Vec2::new(32., 8.))
+                .with_filter(FilterMode::Linear)
+                .with_mipmaps(true)
+                .with_tint(Vec4::new(1., 0.5, 0.5, 1.))
+                .with_opacity(0.5),
+        );
+
+        // A source bigger than the image repeats it
+        context.figure(
+            Figure::new_with_texture(&texture)
+                .with_position(Vec2::new(450., 660.))
+                .with_size(Vec2::new(512., 32.))
+                .with_source(SourceRect::new(Vec2::new(0., 0.), Vec2::new(256., 16.)))
+                .with_wrap(WrapMode::Mirror),
+        );
+
         context.render(event_handler);
     })
 }
+
+fn create_icon(index: u8) -> FigureImage {
+    let size = 8 + index as u32 % 4 * 2;
+    let color = [index * 16, 255 - index * 16, 128, 255];
+    let pixels = color.repeat((size * size) as usize);
+
+    FigureImage::new(UVec2::new(size, size), pixels, FigureImageFormat::Rgba)
+}
+
+fn step_life(life: &mut FigureImage) {
+    let size = LIFE_SIZE as i32;
+    let alive = |x: i32, y: i32| {
+        let x = x.rem_euclid(size) as u32;
+        let y = y.rem_euclid(size) as u32;
+        life.get_pixel(x, y) == Some(ALIVE)
+    };
+
+    let mut changes = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let neighbours = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0) && alive(x + dx, y + dy))
+                .count();
+
+            let next = neighbours == 3 || (neighbours == 2 && alive(x, y));
+            if next != alive(x, y) {
+                changes.push((x as u32, y as u32, next));
+            }
+        }
+    }
+
+    for (x, y, next) in changes {
+        life.set_pixel(x, y, if next { ALIVE } else { DEAD });
+    }
+}
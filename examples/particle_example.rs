@@ -0,0 +1,77 @@
+use std::f32::consts::PI;
+
+use fiji::{
+    BlendMode, Context, EmitterShape, KeyCode, MouseButton, ParticleEmitter, ParticleKind,
+    SpriteSheet,
+};
+use nalgebra_glm::{UVec2, Vec2, Vec4};
+
+pub fn main() {
+    let mut context = Context::new(1280, 720, "Fiji Particle Example");
+
+    let mut fire = ParticleEmitter::new_default()
+        .with_position(Vec2::new(640., 600.))
+        .with_shape(EmitterShape::Cone {
+            direction: -PI / 2.,
+            spread: 0.6,
+        })
+        .with_blend(BlendMode::Additive)
+        .with_spawn_rate(300.)
+        .with_lifetime(0.8, 1.4)
+        .with_velocity(Vec2::new(80., 0.), Vec2::new(160., 0.))
+        .with_acceleration(Vec2::new(-20., -40.), Vec2::new(20., -20.))
+        .with_colors(vec![
+            Vec4::new(1., 0.9, 0.3, 1.),
+            Vec4::new(1., 0.3, 0.1, 0.8),
+            Vec4::new(0.3, 0.1, 0.1, 0.),
+        ])
+        .with_sizes(vec![Vec2::new(24., 24.), Vec2::new(8., 8.)]);
+
+    // The same seed gives the same explosion every time
+    let mut sparks = ParticleEmitter::new_default()
+        .with_shape(EmitterShape::Circle(10.))
+        .with_kind(ParticleKind::Rect)
+        .with_spawn_rate(0.)
+        .with_seed(42)
+        .with_lifetime(0.5, 1.5)
+        .with_velocity(Vec2::new(-300., -400.), Vec2::new(300., 100.))
+        .with_acceleration(Vec2::new(0., 600.), Vec2::new(0., 600.))
+        .with_angular_velocity(-10., 10.)
+        .with_colors(vec![Vec4::new(1., 1., 0.6, 1.), Vec4::new(1., 0.5, 0., 0.)])
+        .with_sizes(vec![Vec2::new(6., 2.)]);
+
+    let texture = context
+        .load_texture("assets/images/sprite_sheet.png")
+        .unwrap();
+    let sheet = SpriteSheet::from_grid(texture.image().clone(), UVec2::new(16, 16));
+    let mut snow = ParticleEmitter::new_default()
+        .with_position(Vec2::new(640., -20.))
+        .with_shape(EmitterShape::Rect(Vec2::new(1280., 0.)))
+        .with_kind(ParticleKind::Figure(sheet.figure(0)))
+        .with_spawn_rate(20.)
+        .with_lifetime(8., 8.)
+        .with_velocity(Vec2::new(-20., 60.), Vec2::new(20., 120.))
+        .with_angular_velocity(-1., 1.)
+        .with_colors(vec![Vec4::new(1., 1., 1., 1.)])
+        .with_sizes(vec![Vec2::new(32., 32.)]);
+
+    context.run(move |input, event_handler, context| {
+        if input.key_pressed(&KeyCode::Escape) {
+            event_handler.exit();
+        }
+
+        if input.mouse_button_pressed(&MouseButton::Left) {
+            sparks.position = *input.mouse_position();
+            sparks.burst(200);
+        }
+        if input.key_pressed(&KeyCode::Space) {
+            fire.emitting = !fire.emitting;
+        }
+
+        context.particles(&mut snow);
+        context.particles(&mut fire);
+        context.particles(&mut sparks);
+
+        context.render(event_handler);
+    })
+}
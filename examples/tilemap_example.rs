@@ -0,0 +1,110 @@
+use fiji::{
+    Camera2D, Context, FigureImage, FigureImageFormat, KeyCode, MouseButton, Tile, TileRotation,
+    Tilemap,
+};
+use nalgebra_glm::{UVec2, Vec2, Vec4};
+
+const TILE_SIZE: u32 = 16;
+const MAP_SIZE: u32 = 256;
+
+pub fn main() {
+    let mut context = Context::new(1280, 720, "Fiji Tilemap Example");
+    context.camera_2d = Camera2D::new_default().with_origin(Vec2::new(640., 360.));
+
+    let mut tilemap = Tilemap::new(
+        create_tileset(),
+        UVec2::new(TILE_SIZE, TILE_SIZE),
+        UVec2::new(MAP_SIZE, MAP_SIZE),
+    )
+    .with_cell_size(Vec2::new(32., 32.));
+
+    // Checkered ground with a second layer of rotated arrows on top
+    let ground = (0..MAP_SIZE * MAP_SIZE)
+        .map(|i| ((i % MAP_SIZE + i / MAP_SIZE) % 2) as i32)
+        .collect::<Vec<_>>();
+    tilemap.set_layer_tiles(0, &ground);
+
+    let arrows = tilemap.add_layer();
+    for i in 0..MAP_SIZE {
+        let rotation = match i % 4 {
+            0 => TileRotation::None,
+            1 => TileRotation::Quarter,
+            2 => TileRotation::Half,
+            _ => TileRotation::ThreeQuarters,
+        };
+        tilemap.set_tile(arrows, i, i, Some(Tile::new(2).with_rotation(rotation)));
+        tilemap.set_tile(
+            arrows,
+            MAP_SIZE - 1 - i,
+            i,
+            Some(Tile::new(2).with_flip_x(true)),
+        );
+    }
+    tilemap.layer_mut(arrows).unwrap().tint = Vec4::new(1., 1., 0.5, 1.);
+
+    context.run(move |input, event_handler, context| {
+        if input.key_pressed(&KeyCode::Escape) {
+            event_handler.exit();
+        }
+
+        let speed = 500. * context.dt() / context.camera_2d.zoom;
+        if input.key_held(&KeyCode::A) {
+            context.camera_2d.position.x -= speed;
+        }
+        if input.key_held(&KeyCode::D) {
+            context.camera_2d.position.x += speed;
+        }
+        if input.key_held(&KeyCode::W) {
+            context.camera_2d.position.y -= speed;
+        }
+        if input.key_held(&KeyCode::S) {
+            context.camera_2d.position.y += speed;
+        }
+        if input.key_held(&KeyCode::R) {
+            context.camera_2d.zoom *= 1.02;
+        }
+        if input.key_held(&KeyCode::F) {
+            context.camera_2d.zoom /= 1.02;
+        }
+
+        // Painting only rebuilds the chunk under the cursor
+        if input.mouse_button_held(&MouseButton::Left) {
+            let world = context.camera_2d.screen_to_world(*input.mouse_position());
+            let cell = (world - tilemap.position).component_div(&tilemap.cell_size);
+            if cell.x >= 0. && cell.y >= 0. {
+                tilemap.set_tile(0, cell.x as u32, cell.y as u32, Some(Tile::new(3)));
+            }
+        }
+
+        context.tilemap(&mut tilemap);
+
+        context.render(event_handler);
+    })
+}
+
+// Four tiles next to each other: two shades of ground, an arrow and a highlight
+fn create_tileset() -> FigureImage {
+    let width = TILE_SIZE * 4;
+    let mut pixels = Vec::with_capacity((width * TILE_SIZE * 4) as usize);
+
+    for y in 0..TILE_SIZE {
+        for x in 0..width {
+            let (tile, x) = (x / TILE_SIZE, x % TILE_SIZE);
+            let color = match tile {
+                0 => [60, 120, 60, 255],
+                1 => [50, 100, 50, 255],
+                // Arrow pointing up
+                2 if x.abs_diff(TILE_SIZE / 2) <= y / 2 => [255, 255, 255, 255],
+                2 => [0, 0, 0, 0],
+                _ => [200, 80, 40, 255],
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+
+    FigureImage::new(
+        UVec2::new(width, TILE_SIZE),
+        pixels,
+        FigureImageFormat::Rgba,
+    )
+}
@@ -1,9 +1,19 @@
-use fiji::{Background, Circle, Context, KeyCode, Rect, Text};
-use nalgebra_glm::{Vec2, Vec3, Vec4};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use fiji::{
+    Arc, Background, Border, Circle, Context, FigureImage, FigureImageFormat, Fill, Glow, KeyCode,
+    NineSlice, Pie, Rect, Ring, Shadow, SliceMode, Text,
+};
+use nalgebra_glm::{UVec2, Vec2, Vec3, Vec4};
 
 pub fn main() {
     let mut context = Context::new(1280, 720, "Fiji UI Example");
-    context.background(Background::new_with_color(Vec3::new(0.3, 0.1, 0.1)));
+    context.background(
+        Background::new_with_color(Vec3::new(0.3, 0.1, 0.1)).with_fill(Fill::vertical(
+            Vec4::new(0.1, 0.2, 0.5, 1.),
+            Vec4::new(0.8, 0.5, 0.4, 1.),
+        )),
+    );
 
     let r = Rect::new_default()
         .with_color(Vec4::new(0.7, 0.3, 0.7, 1.))
@@ -15,6 +25,17 @@ pub fn main() {
         .with_color(Vec4::new(0., 1., 0., 0.3))
         .with_position(r.position + Vec2::new(200., 0.));
 
+    let mut progress = 0.;
+
+    let handwriting = context
+        .load_font_from_path("assets/fonts/comic-sans-ms/comicz.ttf")
+        .unwrap();
+
+    // The corners of the panel keep their size however big the panel gets
+    let panel = NineSlice::new(create_panel_image(), 8., 8., 8., 8.)
+        .with_position(Vec2::new(900., 600.))
+        .with_edge_mode(SliceMode::Tile);
+
     context.run(move |input, event_handler, context| {
         if input.key_pressed(&KeyCode::Escape) {
             event_handler.exit();
@@ -46,6 +67,65 @@ pub fn main() {
                 .with_position(Vec2::new(600., 200.)),
         );
 
+        context.ui_rect(
+            Rect::new_default()
+                .with_position(Vec2::new(150., 550.))
+                .with_size(Vec2::new(250., 150.))
+                .with_corner_radius(12.)
+                .with_fill(
+                    Fill::linear_gradient(Vec2::new(0., 0.), Vec2::new(1., 1.))
+                        .with_stop(0., Vec4::new(0.9, 0.3, 0.5, 1.))
+                        .with_stop(0.5, Vec4::new(0.5, 0.3, 0.9, 1.))
+                        .with_stop(1., Vec4::new(0.2, 0.6, 0.9, 1.)),
+                ),
+        );
+        context.ui_circle(
+            Circle::new_default()
+                .with_radius(60.)
+                .with_position(Vec2::new(450., 550.))
+                .with_fill(
+                    Fill::radial_gradient(Vec2::new(0.35, 0.35), 0.7)
+                        .with_stop(0., Vec4::new(1., 1., 1., 1.))
+                        .with_stop(1., Vec4::new(0.1, 0.4, 0.2, 1.)),
+                ),
+        );
+        context.ui_circle(
+            Circle::new_default()
+                .with_radius(60.)
+                .with_position(Vec2::new(600., 550.))
+                .with_fill(
+                    Fill::conic_gradient(Vec2::new(0.5, 0.5), progress * TAU)
+                        .with_stop(0., Vec4::new(1., 0., 0., 1.))
+                        .with_stop(0.33, Vec4::new(0., 1., 0., 1.))
+                        .with_stop(0.67, Vec4::new(0., 0., 1., 1.))
+                        .with_stop(1., Vec4::new(1., 0., 0., 1.)),
+                ),
+        );
+
+        progress = (progress + 0.2 * context.dt()) % 1.;
+        context.ui_ring(
+            Ring::new_default()
+                .with_color(Vec4::new(0.2, 0.2, 0.2, 1.))
+                .with_position(Vec2::new(1100., 150.))
+                .with_radius(60.)
+                .with_inner_radius(45.),
+        );
+        context.ui_arc(
+            Arc::new_default()
+                .with_color(Vec4::new(0.3, 0.8, 0.4, 1.))
+                .with_position(Vec2::new(1100., 150.))
+                .with_radius(52.5)
+                .with_thickness(15.)
+                .with_angles(-FRAC_PI_2, -FRAC_PI_2 + progress * TAU),
+        );
+        context.ui_pie(
+            Pie::new_default()
+                .with_color(Vec4::new(0.8, 0.6, 0.2, 1.))
+                .with_position(Vec2::new(1100., 400.))
+                .with_radius(60.)
+                .with_angles(0., progress * TAU),
+        );
+
         let text = Text::new_with_text(&format!(
             "fps: {:.3} ({:.3}ms)",
             context.fps(),
@@ -59,6 +139,70 @@ pub fn main() {
             );
         }
 
+        // Card lifted off the background by a soft shadow
+        context.ui_rect(
+            Rect::new_default()
+                .with_color(Vec4::new(0.95, 0.95, 0.97, 1.))
+                .with_position(Vec2::new(800., 200.))
+                .with_size(Vec2::new(220., 140.))
+                .with_corner_radius(10.)
+                .with_border(Border::new(Vec4::new(0.7, 0.7, 0.8, 1.), 1))
+                .with_shadow(
+                    Shadow::new(Vec4::new(0., 0., 0., 0.5), 16.).with_offset(Vec2::new(0., 6.)),
+                ),
+        );
+        context.ui_text(
+            Text::new_with_text("Card title")
+                .with_position(Vec2::new(710., 164.))
+                .with_size(26.)
+                .with_color(Vec4::new(0.1, 0.1, 0.2, 1.)),
+        );
+        context.ui_text(
+            Text::new_with_text("Card subtitle")
+                .with_position(Vec2::new(710., 190.))
+                .with_size(13.)
+                .with_font(handwriting)
+                .with_color(Vec4::new(0.3, 0.3, 0.4, 1.)),
+        );
+        context.ui_circle(
+            Circle::new_default()
+                .with_color(Vec4::new(0.4, 0.9, 1., 1.))
+                .with_position(Vec2::new(800., 400.))
+                .with_radius(20.)
+                .with_glow(Glow::new(Vec4::new(0.4, 0.9, 1., 0.8), 24.).with_spread(4.)),
+        );
+        context.ui_text(
+            Text::new_with_text("Shadowed text")
+                .with_position(Vec2::new(740., 470.))
+                .with_shadow(
+                    Shadow::new(Vec4::new(0., 0., 0., 0.8), 3.).with_offset(Vec2::new(2., 2.)),
+                ),
+        );
+
+        let panel_width = 200. + 100. * (progress * TAU).sin();
+        context.ui_nine_slice(panel.clone().with_size(Vec2::new(panel_width, 120.)));
+
         context.render(event_handler);
     });
 }
+
+// 24x24 frame with an 8 pixel border that has a notch in the middle of every edge
+fn create_panel_image() -> FigureImage {
+    let size = 24;
+    let mut pixels = Vec::new();
+
+    for y in 0..size {
+        for x in 0..size {
+            let edge_distance = x.min(y).min(size - 1 - x).min(size - 1 - y);
+            let color = match edge_distance {
+                0 if x == size / 2 || y == size / 2 => [240, 200, 80, 255],
+                0..=1 => [60, 40, 20, 255],
+                2..=7 => [140, 100, 50, 255],
+                _ => [230, 210, 170, 230],
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+
+    FigureImage::new(UVec2::new(size, size), pixels, FigureImageFormat::Rgba)
+}
@@ -11,8 +11,16 @@ pub use crate::{
         objects::{
             background::*,
             camera::{camera_2d::*, camera_3d::*},
-            obj_2d::{circle::*, figure::*, line::*, polygon::*, rect::*, text::*},
+            fill::*,
+            obj_2d::{
+                anchor::*, animation::*, arc::*, circle::*, ellipse::*, figure::*, font::*,
+                line::*, nine_slice::*, particles::*, path::*, pie::*, points::*, polygon::*,
+                polyline::*, rect::*, ring::*, sprite_sheet::*, text::*, texture::*,
+                texture_atlas::*, tilemap::*,
+            },
             obj_3d::block::*,
+            shadow::*,
+            transform_2d::*,
             *,
         },
     },
@@ -21,7 +29,6 @@ pub use crate::{
 // TODO IN PROGRESS:
 // [ ] - Fix error when window size == 0
 // [ ] - Recreate pipelines when window out of date
-// [ ] - Add image object
 //
 // LATER TODO:
 // [ ] - Keep render objects alive as long as their counter part is alive
@@ -58,9 +65,10 @@ pub use crate::{
 // [ ] - 3D element alignment options
 // [ ] - Add 2D camera option to all pipelines
 // [ ] - Fix text drawing performance!
-// [ ] - Unreverse camera position
 //
 // DONE:
+// [X] - Add image object
+// [X] - Unreverse camera position
 // [X] - Delta time
 // [X] - Make all fiji public objects accessible directly from lib.rs
 // [X] - Camera 2d for all 2d pipelines
@@ -1,12 +1,33 @@
 use std::time::SystemTime;
 
+use image::ImageResult;
+use nalgebra_glm::Vec2;
+
 use crate::{
     input::fiji_events::FijiEventHandler,
     public::objects::{
         background::Background,
         camera::{camera_2d::Camera2D, camera_3d::Camera3D},
-        obj_2d::{circle::Circle, line::Line, polygon::Polygon, rect::Rect},
+        obj_2d::{
+            arc::Arc,
+            circle::Circle,
+            ellipse::Ellipse,
+            font::{FontError, FontHandle},
+            line::Line,
+            nine_slice::NineSlice,
+            particles::ParticleEmitter,
+            path::Path,
+            pie::Pie,
+            points::Points,
+            polygon::Polygon,
+            polyline::Polyline,
+            rect::Rect,
+            ring::Ring,
+            texture::{TextureCache, TextureHandle},
+            tilemap::Tilemap,
+        },
         obj_3d::block::Block,
+        transform_2d::Transform2D,
     },
     rendering::render_containers::{
         event_loop_container::EventLoopContainer, render_container::RenderContainer,
@@ -22,6 +43,10 @@ pub struct Context {
     dt_nano: u128,
     pub camera_2d: Camera2D,
     pub camera_3d: Camera3D,
+    // Applied to every 2D and UI object, reset at the end of each frame
+    transform_2d: Transform2D,
+    transform_stack: Vec<Transform2D>,
+    textures: TextureCache,
 }
 
 impl Context {
@@ -35,55 +60,221 @@ impl Context {
             dt_nano: 0,
             camera_2d: Camera2D::new_default(),
             camera_3d: Camera3D::new_default(),
+            transform_2d: Transform2D::identity(),
+            transform_stack: Vec::new(),
+            textures: TextureCache::new(),
         }
     }
 
+    pub fn push(&mut self) {
+        self.transform_stack.push(self.transform_2d);
+    }
+
+    // Popping without a matching push resets the transform
+    pub fn pop(&mut self) {
+        self.transform_2d = self.transform_stack.pop().unwrap_or_default();
+    }
+
+    pub fn translate(&mut self, translation: Vec2) {
+        self.transform_2d = self.transform_2d.translate(translation);
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.transform_2d = self.transform_2d.rotate(angle);
+    }
+
+    pub fn scale(&mut self, scale: Vec2) {
+        self.transform_2d = self.transform_2d.scale(scale);
+    }
+
+    pub fn apply_transform(&mut self, transform: Transform2D) {
+        self.transform_2d = self.transform_2d * transform;
+    }
+
+    pub fn reset_transform(&mut self) {
+        self.transform_2d = Transform2D::identity();
+    }
+
+    pub fn transform_2d(&self) -> Transform2D {
+        self.transform_2d
+    }
+
     pub fn circle(&mut self, circle: Circle) {
-        self.render_container.circle(circle);
+        self.render_container.circle(circle, self.transform_2d);
     }
 
     pub fn ui_circle(&mut self, circle: Circle) {
-        self.render_container.ui_circle(circle);
+        self.render_container.ui_circle(circle, self.transform_2d);
+    }
+
+    pub fn ellipse(&mut self, ellipse: Ellipse) {
+        self.render_container.ellipse(ellipse, self.transform_2d);
+    }
+
+    pub fn ui_ellipse(&mut self, ellipse: Ellipse) {
+        self.render_container.ui_ellipse(ellipse, self.transform_2d);
+    }
+
+    pub fn arc(&mut self, arc: Arc) {
+        self.render_container.arc(arc, self.transform_2d);
+    }
+
+    pub fn ui_arc(&mut self, arc: Arc) {
+        self.render_container.ui_arc(arc, self.transform_2d);
+    }
+
+    pub fn ring(&mut self, ring: Ring) {
+        self.render_container.ring(ring, self.transform_2d);
+    }
+
+    pub fn ui_ring(&mut self, ring: Ring) {
+        self.render_container.ui_ring(ring, self.transform_2d);
+    }
+
+    pub fn pie(&mut self, pie: Pie) {
+        self.render_container.pie(pie, self.transform_2d);
+    }
+
+    pub fn ui_pie(&mut self, pie: Pie) {
+        self.render_container.ui_pie(pie, self.transform_2d);
     }
 
     pub fn rect(&mut self, rect: Rect) {
-        self.render_container.rect(rect);
+        self.render_container.rect(rect, self.transform_2d);
     }
 
     pub fn ui_rect(&mut self, rect: Rect) {
-        self.render_container.ui_rect(rect);
+        self.render_container.ui_rect(rect, self.transform_2d);
     }
 
     pub fn polygon(&mut self, polygon: Polygon) {
-        self.render_container.polygon(polygon);
+        self.render_container
+            .polygon(polygon, self.transform_2d, self.camera_2d.clamped_zoom());
     }
 
     pub fn ui_polygon(&mut self, polygon: Polygon) {
-        self.render_container.ui_polygon(polygon);
+        self.render_container.ui_polygon(polygon, self.transform_2d);
+    }
+
+    pub fn path(&mut self, path: Path) {
+        self.render_container
+            .path(path, self.transform_2d, self.camera_2d.clamped_zoom());
+    }
+
+    pub fn ui_path(&mut self, path: Path) {
+        self.render_container.ui_path(path, self.transform_2d);
     }
 
     pub fn line(&mut self, line: Line) {
-        self.render_container.line(line);
+        self.render_container.line(line, self.transform_2d);
     }
 
     pub fn ui_line(&mut self, line: Line) {
-        self.render_container.ui_line(line);
+        self.render_container.ui_line(line, self.transform_2d);
+    }
+
+    pub fn polyline(&mut self, polyline: Polyline) {
+        self.render_container.polyline(polyline, self.transform_2d);
+    }
+
+    pub fn ui_polyline(&mut self, polyline: Polyline) {
+        self.render_container
+            .ui_polyline(polyline, self.transform_2d);
     }
 
     pub fn text(&mut self, text: Text) {
-        self.render_container.text(text);
+        self.render_container.text(text, self.transform_2d);
     }
 
     pub fn ui_text(&mut self, text: Text) {
-        self.render_container.ui_text(text);
+        self.render_container.ui_text(text, self.transform_2d);
     }
 
     pub fn figure(&mut self, figure: Figure) {
-        self.render_container.figure(figure);
+        self.render_container.figure(figure, self.transform_2d);
     }
 
     pub fn ui_figure(&mut self, figure: Figure) {
-        self.render_container.ui_figure(figure);
+        self.render_container.ui_figure(figure, self.transform_2d);
+    }
+
+    pub fn nine_slice(&mut self, nine_slice: NineSlice) {
+        self.render_container
+            .nine_slice(nine_slice, self.transform_2d);
+    }
+
+    pub fn ui_nine_slice(&mut self, nine_slice: NineSlice) {
+        self.render_container
+            .ui_nine_slice(nine_slice, self.transform_2d);
+    }
+
+    pub fn points(&mut self, points: Points) {
+        self.render_container.points(points, self.transform_2d);
+    }
+
+    pub fn ui_points(&mut self, points: Points) {
+        self.render_container.ui_points(points, self.transform_2d);
+    }
+
+    // Moves the particles along by the frame time before drawing them
+    pub fn particles(&mut self, emitter: &mut ParticleEmitter) {
+        emitter.update(self.dt());
+        self.render_container.particles(emitter, self.transform_2d);
+    }
+
+    pub fn ui_particles(&mut self, emitter: &mut ParticleEmitter) {
+        emitter.update(self.dt());
+        self.render_container
+            .ui_particles(emitter, self.transform_2d);
+    }
+
+    // Takes the tilemap mutably to rebuild the chunks whose tiles changed
+    pub fn tilemap(&mut self, tilemap: &mut Tilemap) {
+        self.render_container
+            .tilemap(tilemap, self.transform_2d, &self.camera_2d);
+    }
+
+    pub fn ui_tilemap(&mut self, tilemap: &mut Tilemap) {
+        self.render_container.ui_tilemap(tilemap, self.transform_2d);
+    }
+
+    // Loading a file that is still in use returns the already loaded texture
+    pub fn load_texture<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> ImageResult<TextureHandle> {
+        self.textures.load(path.as_ref())
+    }
+
+    // Only the first font of a collection is loaded, the others are loaded with
+    // load_font_collection_from_path
+    pub fn load_font_from_path<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<FontHandle, FontError> {
+        self.render_container.fonts_mut().load_path(path.as_ref())
+    }
+
+    pub fn load_font_from_bytes(&mut self, bytes: &[u8]) -> Result<FontHandle, FontError> {
+        self.render_container.fonts_mut().load_bytes(bytes)
+    }
+
+    pub fn load_font_collection_from_path<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<Vec<FontHandle>, FontError> {
+        self.render_container
+            .fonts_mut()
+            .load_collection_path(path.as_ref())
+    }
+
+    pub fn load_font_collection_from_bytes(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<Vec<FontHandle>, FontError> {
+        self.render_container
+            .fonts_mut()
+            .load_collection_bytes(bytes)
     }
 
     pub fn block(&mut self, block: Block) {
@@ -94,6 +285,15 @@ impl Context {
         self.render_container.background(background);
     }
 
+    // Anti-aliasing smooths the edges of 2D shapes, pixel art usually looks better without it
+    pub fn set_antialiasing(&mut self, antialiasing: bool) {
+        self.render_container.set_antialiasing(antialiasing);
+    }
+
+    pub fn antialiasing(&self) -> bool {
+        self.render_container.antialiasing()
+    }
+
     pub fn run<F>(mut self, mut event_fn: F)
     where
         F: 'static + FnMut(&Input, &mut FijiEventHandler, &mut Context),
@@ -122,9 +322,15 @@ impl Context {
     }
 
     pub fn render(&mut self, fiji_event_handler: &mut FijiEventHandler) {
+        let resolution = self.render_container.resolution();
+        self.camera_2d.update(self.dt(), resolution);
+
         self.render_container
             .render(fiji_event_handler, &self.camera_2d, &self.camera_3d);
 
+        self.transform_2d = Transform2D::identity();
+        self.transform_stack.clear();
+
         let now = SystemTime::now();
         if let Ok(duration) = now.duration_since(self.prev_time) {
             self.dt_nano = duration.as_nanos();
@@ -1,12 +1,28 @@
 use nalgebra_glm::Vec3;
 
+use super::fill::Fill;
+
 #[derive(Clone, Debug)]
 pub struct Background {
     pub color: Vec3,
+    // Drawn over the whole screen on top of color when set
+    pub fill: Option<Fill>,
 }
 
 impl Background {
     pub fn new_with_color(color: Vec3) -> Self {
-        Self { color }
+        Self { color, fill: None }
+    }
+
+    pub fn new_with_fill<T: Into<Fill>>(fill: T) -> Self {
+        Self {
+            color: Vec3::new(0., 0., 0.),
+            fill: Some(fill.into()),
+        }
+    }
+
+    pub fn with_fill<T: Into<Fill>>(mut self, fill: T) -> Self {
+        self.fill = Some(fill.into());
+        self
     }
 }
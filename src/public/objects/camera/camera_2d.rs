@@ -1,14 +1,269 @@
-use nalgebra_glm::Vec2;
+use nalgebra_glm::{self as glm, Vec2};
+use rand::Rng;
+
+use crate::public::objects::transform_2d::Transform2D;
+
+const DEFAULT_SHAKE_DECAY: f32 = 5.;
+// Shakes weaker than this (in pixels) are stopped
+const MIN_SHAKE: f32 = 0.1;
+// Lower zooms, including zero and negative ones, are drawn at this zoom
+const MIN_ZOOM: f32 = 0.001;
 
 #[derive(Debug)]
 pub struct Camera2D {
+    // World position shown at the origin of the viewport
     pub position: Vec2,
+    // Values above 1 zoom in
+    pub zoom: f32,
+    pub rotation: f32,
+    // Screen position in pixels where the camera position ends up, use the center of the
+    // window to keep the camera position in the middle of the screen
+    pub origin: Vec2,
+    // Size in screen pixels of the area around the camera position in which a followed
+    // target can move without moving the camera
+    pub deadzone: Option<Vec2>,
+    // Minimum and maximum world position the visible area is kept inside of.
+    // Rotation is not taken into account.
+    pub bounds: Option<(Vec2, Vec2)>,
+    // How fast shaking fades out, higher is faster
+    pub shake_decay: f32,
+    follow_target: Option<(Vec2, f32)>,
+    shake_strength: f32,
+    shake_offset: Vec2,
 }
 
 impl Camera2D {
     pub fn new_default() -> Self {
         Self {
             position: Vec2::new(0., 0.),
+            zoom: 1.,
+            rotation: 0.,
+            origin: Vec2::new(0., 0.),
+            deadzone: None,
+            bounds: None,
+            shake_decay: DEFAULT_SHAKE_DECAY,
+            follow_target: None,
+            shake_strength: 0.,
+            shake_offset: Vec2::new(0., 0.),
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom.max(MIN_ZOOM);
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_deadzone(mut self, size: Vec2) -> Self {
+        self.deadzone = Some(size);
+        self
+    }
+
+    pub fn with_bounds(mut self, min: Vec2, max: Vec2) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    pub fn with_shake_decay(mut self, shake_decay: f32) -> Self {
+        self.shake_decay = shake_decay;
+        self
+    }
+
+    // Moves the camera towards target every frame, a higher lerp_speed catches up faster.
+    // Call it every frame with the new target position to keep following it.
+    pub fn follow(&mut self, target: Vec2, lerp_speed: f32) {
+        self.follow_target = Some((target, lerp_speed));
+    }
+
+    pub fn stop_following(&mut self) {
+        self.follow_target = None;
+    }
+
+    // Shakes the screen by up to intensity pixels, fading out over time
+    pub fn shake(&mut self, intensity: f32) {
+        self.shake_strength = self.shake_strength.max(intensity);
+    }
+
+    pub fn world_to_screen(&self, world_position: Vec2) -> Vec2 {
+        self.view_transform().transform_point(world_position)
+    }
+
+    pub fn screen_to_world(&self, screen_position: Vec2) -> Vec2 {
+        let offset = screen_position - self.origin - self.shake_offset;
+        let offset = glm::rotate_vec2(&offset, self.rotation);
+        self.position + offset / self.clamped_zoom()
+    }
+
+    // Changes the zoom while keeping the world position under screen_position in place,
+    // for example to zoom towards the mouse cursor
+    pub fn zoom_at(&mut self, screen_position: Vec2, zoom: f32) {
+        let before = self.screen_to_world(screen_position);
+        self.zoom = zoom.max(MIN_ZOOM);
+        let after = self.screen_to_world(screen_position);
+        self.position += before - after;
+    }
+
+    // The zoom field is public, so it can still be set below the minimum
+    pub(crate) fn clamped_zoom(&self) -> f32 {
+        self.zoom.max(MIN_ZOOM)
+    }
+
+    // Maps world coordinates to screen pixels
+    pub(crate) fn view_transform(&self) -> Transform2D {
+        let zoom = self.clamped_zoom();
+        Transform2D::from_translation(self.origin + self.shake_offset)
+            .rotate(-self.rotation)
+            .scale(Vec2::new(zoom, zoom))
+            .translate(-self.position)
+    }
+
+    pub(crate) fn update(&mut self, dt: f32, resolution: Vec2) {
+        if let Some((target, lerp_speed)) = self.follow_target {
+            self.move_towards(target, lerp_speed, dt);
+        }
+
+        if let Some((min, max)) = self.bounds {
+            self.clamp_to_bounds(min, max, resolution);
+        }
+
+        self.update_shake(dt);
+    }
+
+    fn move_towards(&mut self, target: Vec2, lerp_speed: f32, dt: f32) {
+        let distance = target - self.position;
+        let goal = match self.deadzone {
+            Some(size) => {
+                // Only move as far as needed to get the target back into the deadzone
+                let half_size = size / (2. * self.clamped_zoom());
+                self.position + distance - glm::clamp_vec(&distance, &-half_size, &half_size)
+            }
+            None => target,
+        };
+
+        // Exponential smoothing, so the speed does not depend on the frame rate
+        let t = 1. - (-lerp_speed * dt).exp();
+        self.position += (goal - self.position) * t.clamp(0., 1.);
+    }
+
+    fn clamp_to_bounds(&mut self, min: Vec2, max: Vec2, resolution: Vec2) {
+        // Visible world area relative to the camera position
+        let zoom = self.clamped_zoom();
+        let view_min = -self.origin / zoom;
+        let view_max = (resolution - self.origin) / zoom;
+
+        for i in 0..2 {
+            let lowest = min[i] - view_min[i];
+            let highest = max[i] - view_max[i];
+            self.position[i] = if lowest > highest {
+                // The bounds are smaller than the view, so center them instead
+                (lowest + highest) * 0.5
+            } else {
+                self.position[i].clamp(lowest, highest)
+            };
+        }
+    }
+
+    fn update_shake(&mut self, dt: f32) {
+        if self.shake_strength < MIN_SHAKE {
+            self.shake_strength = 0.;
+            self.shake_offset = Vec2::new(0., 0.);
+            return;
+        }
+
+        let mut rng = rand::thread_rng();
+        self.shake_offset =
+            Vec2::new(rng.gen_range(-1. ..1.), rng.gen_range(-1. ..1.)) * self.shake_strength;
+        self.shake_strength *= (-self.shake_decay * dt).exp();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use nalgebra_glm::Vec2;
+
+    use super::{Camera2D, MIN_ZOOM};
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!((a - b).norm() < 1e-3, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn screen_and_world_round_trip() {
+        let camera = Camera2D::new_default()
+            .with_position(Vec2::new(30., -20.))
+            .with_zoom(2.5)
+            .with_rotation(0.7)
+            .with_origin(Vec2::new(640., 360.));
+
+        assert_near(camera.world_to_screen(camera.position), camera.origin);
+        for point in [
+            Vec2::new(100., 250.),
+            Vec2::new(-75., 12.5),
+            Vec2::new(0., 0.),
+        ] {
+            assert_near(camera.screen_to_world(camera.world_to_screen(point)), point);
+            assert_near(camera.world_to_screen(camera.screen_to_world(point)), point);
+        }
+    }
+
+    #[test]
+    fn world_to_screen_rotates_and_zooms_around_the_origin() {
+        let camera = Camera2D::new_default()
+            .with_position(Vec2::new(10., 10.))
+            .with_zoom(2.)
+            .with_rotation(FRAC_PI_2)
+            .with_origin(Vec2::new(400., 300.));
+
+        // Turning the camera clockwise turns the world the other way on screen
+        assert_near(
+            camera.world_to_screen(Vec2::new(11., 10.)),
+            Vec2::new(400., 298.),
+        );
+        assert_near(
+            camera.world_to_screen(Vec2::new(10., 13.)),
+            Vec2::new(406., 300.),
+        );
+    }
+
+    #[test]
+    fn zoom_at_keeps_the_point_under_the_cursor() {
+        let mut camera = Camera2D::new_default()
+            .with_rotation(0.3)
+            .with_origin(Vec2::new(400., 300.));
+        let cursor = Vec2::new(120., 80.);
+        let world = camera.screen_to_world(cursor);
+        camera.zoom_at(cursor, 4.);
+        assert_near(camera.world_to_screen(world), cursor);
+    }
+
+    #[test]
+    fn clamped_zoom_stays_above_the_minimum() {
+        let mut camera = Camera2D::new_default().with_zoom(0.);
+        assert_eq!(camera.zoom, MIN_ZOOM);
+
+        camera.zoom = 2.;
+        assert_eq!(camera.clamped_zoom(), 2.);
+        for zoom in [0., -3.] {
+            camera.zoom = zoom;
+            assert_eq!(camera.clamped_zoom(), MIN_ZOOM);
+            let world = camera.screen_to_world(Vec2::new(10., 10.));
+            assert!(world.x.is_finite() && world.y.is_finite());
         }
     }
 }
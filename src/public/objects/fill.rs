@@ -0,0 +1,92 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    pub offset: f32,
+    pub color: Vec4,
+}
+
+impl ColorStop {
+    pub fn new(offset: f32, color: Vec4) -> Self {
+        Self { offset, color }
+    }
+}
+
+// Gradient coordinates are relative to the bounding box of the filled object:
+// (0, 0) is its top left corner and (1, 1) its bottom right corner.
+// Only the first 8 stops are used.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fill {
+    Solid(Vec4),
+    LinearGradient {
+        start: Vec2,
+        end: Vec2,
+        stops: Vec<ColorStop>,
+    },
+    RadialGradient {
+        center: Vec2,
+        radius: f32,
+        stops: Vec<ColorStop>,
+    },
+    // The angle is in radians, clockwise on screen starting at the positive x axis
+    ConicGradient {
+        center: Vec2,
+        start_angle: f32,
+        stops: Vec<ColorStop>,
+    },
+}
+
+impl Fill {
+    pub fn linear_gradient(start: Vec2, end: Vec2) -> Self {
+        Fill::LinearGradient {
+            start,
+            end,
+            stops: Vec::new(),
+        }
+    }
+
+    pub fn radial_gradient(center: Vec2, radius: f32) -> Self {
+        Fill::RadialGradient {
+            center,
+            radius,
+            stops: Vec::new(),
+        }
+    }
+
+    pub fn conic_gradient(center: Vec2, start_angle: f32) -> Self {
+        Fill::ConicGradient {
+            center,
+            start_angle,
+            stops: Vec::new(),
+        }
+    }
+
+    pub fn vertical(top: Vec4, bottom: Vec4) -> Self {
+        Self::linear_gradient(Vec2::new(0.5, 0.), Vec2::new(0.5, 1.))
+            .with_stop(0., top)
+            .with_stop(1., bottom)
+    }
+
+    pub fn horizontal(left: Vec4, right: Vec4) -> Self {
+        Self::linear_gradient(Vec2::new(0., 0.5), Vec2::new(1., 0.5))
+            .with_stop(0., left)
+            .with_stop(1., right)
+    }
+
+    // Does nothing for solid fills
+    pub fn with_stop(mut self, offset: f32, color: Vec4) -> Self {
+        match &mut self {
+            Fill::Solid(_) => (),
+            Fill::LinearGradient { stops, .. }
+            | Fill::RadialGradient { stops, .. }
+            | Fill::ConicGradient { stops, .. } => stops.push(ColorStop::new(offset, color)),
+        }
+        self
+    }
+}
+
+impl From<Vec4> for Fill {
+    fn from(color: Vec4) -> Self {
+        Fill::Solid(color)
+    }
+}
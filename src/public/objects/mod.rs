@@ -1,20 +1,125 @@
 pub mod background;
 pub mod camera;
+pub mod fill;
 pub mod obj_2d;
 pub mod obj_3d;
+pub mod shadow;
+pub mod transform_2d;
 
 use nalgebra_glm::Vec4;
 
+use self::obj_2d::line::LineJoin;
+
 const DEFAULT_COLOR: Vec4 = Vec4::new(1., 1., 1., 1.);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderAlignment {
+    Inner,
+    Center,
+    Outer,
+}
+
 #[derive(Clone, Debug)]
 pub struct Border {
     pub color: Vec4,
     pub width: u32,
+    pub alignment: BorderAlignment,
+    // Only used where the outline has sharp corners, like polygons
+    pub join: LineJoin,
+    // Dashes are drawn on rects, circles, ellipses and polygons, other shapes keep a solid border
+    pub style: StrokeStyle,
 }
 
 impl Border {
     pub fn new(color: Vec4, width: u32) -> Self {
-        Border { color, width }
+        Border {
+            color,
+            width,
+            alignment: BorderAlignment::Outer,
+            join: LineJoin::Miter,
+            style: StrokeStyle::new_solid(),
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: BorderAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_style(mut self, style: StrokeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // How far the border reaches outside of the shape it is drawn around
+    pub(crate) fn outer_extent(&self) -> f32 {
+        match self.alignment {
+            BorderAlignment::Inner => 0.,
+            BorderAlignment::Center => self.width as f32 * 0.5,
+            BorderAlignment::Outer => self.width as f32,
+        }
+    }
+}
+
+// Dash pattern of a line or border. The pattern is measured along the length of the stroke, so
+// dashes stay evenly spaced around corners.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    // Alternating dash and gap lengths, empty for a solid stroke
+    pub dashes: Vec<f32>,
+    // Moves the pattern along the stroke, increasing it every frame gives marching ants
+    pub dash_offset: f32,
+    // Gives every dash round ends, dashes with a length of 0 become dots
+    pub round_caps: bool,
+}
+
+impl StrokeStyle {
+    pub fn new_solid() -> Self {
+        Self {
+            dashes: Vec::new(),
+            dash_offset: 0.,
+            round_caps: false,
+        }
+    }
+
+    pub fn new_dashed(dashes: Vec<f32>) -> Self {
+        Self {
+            dashes,
+            dash_offset: 0.,
+            round_caps: false,
+        }
+    }
+
+    // Round dots as wide as the stroke, spacing is the distance between their centres
+    pub fn new_dotted(spacing: f32) -> Self {
+        Self {
+            dashes: vec![0., spacing],
+            dash_offset: 0.,
+            round_caps: true,
+        }
+    }
+
+    pub fn with_dashes(mut self, dashes: Vec<f32>) -> Self {
+        self.dashes = dashes;
+        self
+    }
+
+    pub fn with_dash_offset(mut self, dash_offset: f32) -> Self {
+        self.dash_offset = dash_offset;
+        self
+    }
+
+    pub fn with_round_caps(mut self, round_caps: bool) -> Self {
+        self.round_caps = round_caps;
+        self
+    }
+
+    pub(crate) fn is_dashed(&self) -> bool {
+        !self.dashes.is_empty()
     }
 }
@@ -0,0 +1,31 @@
+use nalgebra_glm::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    // Offset of the anchor point from the center of the object, as a fraction of its size
+    pub(crate) fn offset(&self) -> Vec2 {
+        match self {
+            Anchor::TopLeft => Vec2::new(-0.5, -0.5),
+            Anchor::Top => Vec2::new(0., -0.5),
+            Anchor::TopRight => Vec2::new(0.5, -0.5),
+            Anchor::Left => Vec2::new(-0.5, 0.),
+            Anchor::Center => Vec2::new(0., 0.),
+            Anchor::Right => Vec2::new(0.5, 0.),
+            Anchor::BottomLeft => Vec2::new(-0.5, 0.5),
+            Anchor::Bottom => Vec2::new(0., 0.5),
+            Anchor::BottomRight => Vec2::new(0.5, 0.5),
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationMode {
+    // Stops on the last frame
+    Once,
+    Loop,
+    // Plays forwards and then backwards
+    PingPong,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationEvent {
+    // A frame with an event was reached, contains the name of the event
+    Frame(String),
+    Looped,
+    Finished,
+}
+
+// Plays a list of sprite sheet frames, call update every frame and
+// draw the frame returned by current_frame
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub frames: Vec<usize>,
+    pub fps: f32,
+    pub mode: AnimationMode,
+    // Events fired when the animation reaches the frame at the given position in frames
+    events: Vec<(usize, String)>,
+    position: usize,
+    forwards: bool,
+    time: f32,
+    paused: bool,
+    finished: bool,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<usize>, fps: f32) -> Self {
+        Self {
+            frames,
+            fps,
+            mode: AnimationMode::Loop,
+            events: Vec::new(),
+            position: 0,
+            forwards: true,
+            time: 0.,
+            paused: false,
+            finished: false,
+        }
+    }
+
+    pub fn from_range(frames: Range<usize>, fps: f32) -> Self {
+        Self::new(frames.collect(), fps)
+    }
+
+    pub fn with_mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_event(mut self, position: usize, name: &str) -> Self {
+        self.events.push((position, name.to_string()));
+        self
+    }
+
+    // Advances the animation and returns the events that happened in the meantime
+    pub fn update(&mut self, dt: f32) -> Vec<AnimationEvent> {
+        let mut events = Vec::new();
+        if self.paused || self.finished || self.frames.is_empty() || self.fps <= 0. {
+            return events;
+        }
+
+        let frame_time = 1. / self.fps;
+        self.time += dt;
+        while self.time >= frame_time && !self.finished {
+            self.time -= frame_time;
+            self.advance(&mut events);
+        }
+
+        events
+    }
+
+    // Index into the sprite sheet of the frame that should be drawn
+    pub fn current_frame(&self) -> usize {
+        self.frames.get(self.position).copied().unwrap_or(0)
+    }
+
+    pub fn play(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.forwards = true;
+        self.time = 0.;
+        self.finished = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn advance(&mut self, events: &mut Vec<AnimationEvent>) {
+        let last = self.frames.len() - 1;
+
+        match self.mode {
+            AnimationMode::Once => {
+                if self.position >= last {
+                    self.finished = true;
+                    events.push(AnimationEvent::Finished);
+                    return;
+                }
+                self.position += 1;
+            }
+            AnimationMode::Loop => {
+                if self.position >= last {
+                    self.position = 0;
+                    events.push(AnimationEvent::Looped);
+                } else {
+                    self.position += 1;
+                }
+            }
+            AnimationMode::PingPong => {
+                if last == 0 {
+                    events.push(AnimationEvent::Looped);
+                } else {
+                    if self.forwards && self.position >= last {
+                        self.forwards = false;
+                    } else if !self.forwards && self.position == 0 {
+                        self.forwards = true;
+                    }
+
+                    if self.forwards {
+                        self.position += 1;
+                    } else {
+                        self.position -= 1;
+                        // Back at the start, also when turning around straight to it
+                        if self.position == 0 {
+                            events.push(AnimationEvent::Looped);
+                        }
+                    }
+                }
+            }
+        }
+
+        events.extend(
+            self.events
+                .iter()
+                .filter(|(position, _)| *position == self.position)
+                .map(|(_, name)| AnimationEvent::Frame(name.clone())),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Animation, AnimationEvent, AnimationMode};
+
+    // Frames shown after each of the given number of one second updates
+    fn steps(animation: &mut Animation, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                animation.update(1.);
+                animation.current_frame()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn once_stops_on_last_frame() {
+        let mut animation = Animation::from_range(0..3, 1.).with_mode(AnimationMode::Once);
+        assert_eq!(animation.update(1.), vec![]);
+        assert_eq!(animation.update(1.), vec![]);
+        assert_eq!(animation.update(1.), vec![AnimationEvent::Finished]);
+        assert!(animation.is_finished());
+        assert_eq!(animation.current_frame(), 2);
+        assert_eq!(animation.update(1.), vec![]);
+
+        animation.reset();
+        assert!(!animation.is_finished());
+        assert_eq!(animation.current_frame(), 0);
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let mut animation = Animation::from_range(0..3, 1.);
+        assert_eq!(steps(&mut animation, 5), vec![1, 2, 0, 1, 2]);
+
+        let mut animation = Animation::from_range(0..3, 1.);
+        animation.update(2.);
+        assert_eq!(animation.update(1.), vec![AnimationEvent::Looped]);
+    }
+
+    #[test]
+    fn ping_pong_turns_around() {
+        let mut animation = Animation::from_range(0..3, 1.).with_mode(AnimationMode::PingPong);
+        assert_eq!(steps(&mut animation, 8), vec![1, 2, 1, 0, 1, 2, 1, 0]);
+
+        let mut animation = Animation::from_range(0..3, 1.).with_mode(AnimationMode::PingPong);
+        let looped = (0..8)
+            .map(|_| animation.update(1.).contains(&AnimationEvent::Looped))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            looped,
+            vec![false, false, false, true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn ping_pong_with_two_frames_loops() {
+        let mut animation = Animation::from_range(0..2, 1.).with_mode(AnimationMode::PingPong);
+        assert_eq!(animation.update(1.), vec![]);
+        assert_eq!(animation.current_frame(), 1);
+        assert_eq!(animation.update(1.), vec![AnimationEvent::Looped]);
+        assert_eq!(animation.current_frame(), 0);
+        assert_eq!(animation.update(1.), vec![]);
+        assert_eq!(animation.update(1.), vec![AnimationEvent::Looped]);
+    }
+
+    #[test]
+    fn ping_pong_with_one_frame_loops() {
+        let mut animation = Animation::new(vec![4], 1.).with_mode(AnimationMode::PingPong);
+        assert_eq!(animation.update(1.), vec![AnimationEvent::Looped]);
+        assert_eq!(animation.current_frame(), 4);
+    }
+
+    #[test]
+    fn frame_events() {
+        let mut animation = Animation::from_range(0..4, 1.).with_event(2, "step");
+        assert_eq!(animation.update(1.), vec![]);
+        assert_eq!(
+            animation.update(1.),
+            vec![AnimationEvent::Frame("step".to_string())]
+        );
+        assert_eq!(animation.update(1.), vec![]);
+        assert_eq!(animation.update(1.), vec![AnimationEvent::Looped]);
+    }
+
+    #[test]
+    fn large_dt_catches_up() {
+        let mut animation = Animation::from_range(0..4, 10.).with_event(1, "step");
+        let events = animation.update(0.55);
+        assert_eq!(animation.current_frame(), 1);
+        assert_eq!(
+            events,
+            vec![
+                AnimationEvent::Frame("step".to_string()),
+                AnimationEvent::Looped,
+                AnimationEvent::Frame("step".to_string()),
+            ]
+        );
+
+        // The left over time counts towards the next frame
+        assert_eq!(animation.update(0.06), vec![]);
+        assert_eq!(animation.current_frame(), 2);
+    }
+
+    #[test]
+    fn paused_does_not_advance() {
+        let mut animation = Animation::from_range(0..3, 1.);
+        animation.pause();
+        assert_eq!(animation.update(5.), vec![]);
+        assert_eq!(animation.current_frame(), 0);
+        animation.play();
+        animation.update(1.);
+        assert_eq!(animation.current_frame(), 1);
+    }
+}
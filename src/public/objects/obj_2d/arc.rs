@@ -0,0 +1,64 @@
+use std::f32::consts::PI;
+
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::public::objects::{Border, DEFAULT_COLOR};
+
+use super::DEFAULT_POSITION_2D;
+
+// Angles are in radians, starting at the positive x axis and going clockwise on screen
+#[derive(Clone, Debug)]
+pub struct Arc {
+    pub color: Vec4,
+    pub position: Vec2,
+    pub radius: f32,
+    pub thickness: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub border: Option<Border>,
+}
+
+impl Arc {
+    pub fn new_default() -> Self {
+        Self {
+            color: DEFAULT_COLOR,
+            position: DEFAULT_POSITION_2D,
+            radius: 10.,
+            thickness: 2.,
+            start_angle: 0.,
+            end_angle: PI,
+            border: None,
+        }
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn with_angles(mut self, start_angle: f32, end_angle: f32) -> Self {
+        self.start_angle = start_angle;
+        self.end_angle = end_angle;
+        self
+    }
+
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
+}
@@ -1,24 +1,35 @@
 use nalgebra_glm::{Vec2, Vec4};
 
-use crate::public::objects::{Border, DEFAULT_COLOR};
+use crate::public::objects::{
+    fill::Fill,
+    shadow::{Glow, Shadow},
+    Border, DEFAULT_COLOR,
+};
 
 use super::DEFAULT_POSITION_2D;
 
 #[derive(Clone, Debug)]
 pub struct Circle {
     pub color: Vec4,
+    // Replaces color when set
+    pub fill: Option<Fill>,
     pub position: Vec2,
     pub radius: f32,
     pub border: Option<Border>,
+    pub shadow: Option<Shadow>,
+    pub glow: Option<Glow>,
 }
 
 impl Circle {
     pub fn new_default() -> Self {
         Self {
             color: DEFAULT_COLOR,
+            fill: None,
             position: DEFAULT_POSITION_2D,
             radius: 10.,
             border: None,
+            shadow: None,
+            glow: None,
         }
     }
 
@@ -27,6 +38,11 @@ impl Circle {
         self
     }
 
+    pub fn with_fill<T: Into<Fill>>(mut self, fill: T) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
     pub fn with_position(mut self, position: Vec2) -> Self {
         self.position = position;
         self
@@ -41,4 +57,14 @@ impl Circle {
         self.border = Some(border);
         self
     }
+
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    pub fn with_glow(mut self, glow: Glow) -> Self {
+        self.glow = Some(glow);
+        self
+    }
 }
@@ -1,18 +1,43 @@
-use image::ImageBuffer;
-use nalgebra_glm::{UVec2, Vec2};
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+use image::{DynamicImage, ImageBuffer, ImageResult};
+use nalgebra_glm::{UVec2, Vec2, Vec4};
 use vulkano::buffer::BufferContents;
 
 use crate::Border;
 
-use super::DEFAULT_POSITION_2D;
+use super::{texture::TextureHandle, DEFAULT_POSITION_2D};
+
+// Every new image gets its own id, clones share it so the image is only uploaded once
+static NEXT_IMAGE_ID: AtomicU64 = AtomicU64::new(0);
+// More changed regions than this are merged into one
+const MAX_DIRTY_REGIONS: usize = 8;
 
 #[derive(Clone)]
 pub struct Figure {
     pub(crate) image: FigureImage,
-    pub(crate) image_changed: bool,
     pub position: Vec2,
     pub size: Vec2,
     pub border: Option<Border>,
+    // Part of the image that is drawn, the whole image is drawn when None
+    pub source: Option<SourceRect>,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub filter: FilterMode,
+    // Only visible when the source is bigger than the image
+    pub wrap: WrapMode,
+    // Smooths out images drawn a lot smaller than their size. Changing a mipmapped image
+    // uploads it again completely.
+    pub mipmaps: bool,
+    // Multiplied with the colors of the image
+    pub tint: Vec4,
+    pub opacity: f32,
 }
 
 impl Figure {
@@ -20,11 +45,137 @@ impl Figure {
         let size = Vec2::new(image.size.x as f32, image.size.y as f32);
         Self {
             image,
-            image_changed: true,
             position: DEFAULT_POSITION_2D,
             size,
             border: None,
+            source: None,
+            flip_x: false,
+            flip_y: false,
+            filter: FilterMode::Nearest,
+            wrap: WrapMode::Clamp,
+            mipmaps: false,
+            tint: Vec4::new(1., 1., 1., 1.),
+            opacity: 1.,
+        }
+    }
+
+    pub fn new_with_texture(texture: &TextureHandle) -> Self {
+        Self::new_with_image(texture.image().clone())
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceRect) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    pub fn with_tint(mut self, tint: Vec4) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    // Tint with the opacity applied, used as vertex color
+    pub(crate) fn color(&self) -> [f32; 4] {
+        [
+            self.tint.x,
+            self.tint.y,
+            self.tint.z,
+            self.tint.w * self.opacity.clamp(0., 1.),
+        ]
+    }
+
+    // Texture coordinates of the top left and bottom right corner of the figure
+    pub(crate) fn uv_region(&self) -> ([f32; 2], [f32; 2]) {
+        let (mut min, mut max) = match &self.source {
+            Some(source) if self.image.size.x > 0 && self.image.size.y > 0 => {
+                let image_size = Vec2::new(self.image.size.x as f32, self.image.size.y as f32);
+                (
+                    source.position.component_div(&image_size),
+                    (source.position + source.size).component_div(&image_size),
+                )
+            }
+            _ => (Vec2::new(0., 0.), Vec2::new(1., 1.)),
+        };
+
+        if self.flip_x {
+            std::mem::swap(&mut min.x, &mut max.x);
+        }
+        if self.flip_y {
+            std::mem::swap(&mut min.y, &mut max.y);
         }
+
+        (min.into(), max.into())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FilterMode {
+    // Keeps pixels sharp, for pixel art
+    Nearest,
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WrapMode {
+    Clamp,
+    Repeat,
+    Mirror,
+}
+
+// Rectangle in image pixels, (0, 0) is the top left corner of the image
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SourceRect {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl SourceRect {
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self { position, size }
     }
 }
 
@@ -37,16 +188,35 @@ pub enum FigureImageFormat {
 #[derive(Clone)]
 pub struct FigureImage {
     pub(crate) size: UVec2,
-    pub(crate) image: Vec<u8>,
+    pub(crate) image: Arc<Vec<u8>>,
     pub(crate) image_format: FigureImageFormat,
+    pub(crate) shared: Arc<SharedImageState>,
+}
+
+// State shared by all clones of an image, as long as one of them is alive
+// the uploaded image is kept
+pub(crate) struct SharedImageState {
+    pub(crate) id: u64,
+    // Regions changed since the last upload as position and size
+    dirty_regions: Mutex<Vec<(UVec2, UVec2)>>,
+}
+
+impl SharedImageState {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+            dirty_regions: Mutex::new(Vec::new()),
+        })
+    }
 }
 
 impl FigureImage {
     pub fn new(size: UVec2, image: Vec<u8>, image_format: FigureImageFormat) -> Self {
         Self {
             size,
-            image,
+            image: Arc::new(image),
             image_format,
+            shared: SharedImageState::new(),
         }
     }
 
@@ -61,12 +231,194 @@ impl FigureImage {
             *pixel = image::Rgba::<u8>([0, 0, 0, 255]);
         }
 
-        println!("{:?}", imgbuf.as_bytes().to_vec());
+        Self::new(size, imgbuf.as_bytes().to_vec(), FigureImageFormat::Rgba)
+    }
 
-        Self {
-            size,
-            image: imgbuf.as_bytes().to_vec(),
-            image_format: FigureImageFormat::Rgba,
+    // Supports PNG, JPEG, BMP and QOI among others, the format is guessed from the extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        Ok(Self::from_dynamic_image(image::open(path)?))
+    }
+
+    // Like from_path, but the format is guessed from the contents
+    pub fn from_memory(bytes: &[u8]) -> ImageResult<Self> {
+        Ok(Self::from_dynamic_image(image::load_from_memory(bytes)?))
+    }
+
+    fn from_dynamic_image(image: DynamicImage) -> Self {
+        let image = image.into_rgba8();
+        Self::new(
+            UVec2::new(image.width(), image.height()),
+            image.into_raw(),
+            FigureImageFormat::Rgba,
+        )
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    // Returns the color as RGBA, also for RGB images
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.size.x || y >= self.size.y {
+            return None;
+        }
+
+        let channels = self.image_format.channels();
+        let index = (y * self.size.x + x) as usize * channels;
+        let pixel = self.image.get(index..index + channels)?;
+        Some(match self.image_format {
+            FigureImageFormat::Rgb => [pixel[0], pixel[1], pixel[2], 255],
+            FigureImageFormat::Rgba => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        })
+    }
+
+    // Pixels outside of the image are ignored, RGB images ignore the alpha
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        self.fill_rect(UVec2::new(x, y), UVec2::new(1, 1), color);
+    }
+
+    pub fn fill_rect(&mut self, position: UVec2, size: UVec2, color: [u8; 4]) {
+        let channels = self.image_format.channels();
+        self.update_region(position, size, |_, row| {
+            for pixel in row.chunks_exact_mut(channels) {
+                pixel.copy_from_slice(&color[..channels]);
+            }
+        });
+    }
+
+    // Raw pixels in the format of the image, row by row. Marks the whole image as changed,
+    // use set_pixel or fill_rect to only upload the pixels that changed.
+    pub fn pixels_mut(&mut self) -> &mut [u8] {
+        self.mark_dirty(UVec2::new(0, 0), self.size);
+        self.unique_pixels()
+    }
+
+    // Calls change with the index and the pixels of every row of the region, parts outside of
+    // the image are left out. Only the region is uploaded again, also when other clones share
+    // the pixels, as they share the uploaded image too.
+    pub(crate) fn update_region(
+        &mut self,
+        position: UVec2,
+        size: UVec2,
+        mut change: impl FnMut(u32, &mut [u8]),
+    ) {
+        let end = (position + size).inf(&self.size);
+        if position.x >= end.x || position.y >= end.y {
+            return;
+        }
+
+        self.mark_dirty(position, end - position);
+
+        let image_width = self.size.x;
+        let channels = self.image_format.channels();
+        let pixels = self.unique_pixels();
+        for y in position.y..end.y {
+            let start = (y * image_width + position.x) as usize * channels;
+            let row_end = (y * image_width + end.x) as usize * channels;
+            change(y - position.y, &mut pixels[start..row_end]);
+        }
+    }
+
+    // Copies the pixels first if other clones still use them
+    fn unique_pixels(&mut self) -> &mut Vec<u8> {
+        let length = (self.size.x * self.size.y) as usize * self.image_format.channels();
+        let pixels = Arc::make_mut(&mut self.image);
+        pixels.resize(length, 255);
+        pixels
+    }
+
+    fn mark_dirty(&self, position: UVec2, size: UVec2) {
+        let mut dirty_regions = self.shared.dirty_regions.lock().unwrap();
+        dirty_regions.push((position, size));
+
+        if dirty_regions.len() > MAX_DIRTY_REGIONS {
+            let min = dirty_regions
+                .iter()
+                .fold(self.size, |min, (position, _)| min.inf(position));
+            let max = dirty_regions
+                .iter()
+                .fold(UVec2::new(0, 0), |max, (position, size)| {
+                    max.sup(&(position + size))
+                });
+            *dirty_regions = vec![(min, max - min)];
         }
     }
+
+    // Regions changed since the last call, the first upload doesn't need them
+    pub(crate) fn take_dirty_regions(&self) -> Vec<(UVec2, UVec2)> {
+        std::mem::take(&mut *self.shared.dirty_regions.lock().unwrap())
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.shared.id
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakFigureImage {
+        WeakFigureImage {
+            size: self.size,
+            image: Arc::downgrade(&self.image),
+            image_format: self.image_format.clone(),
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    // Pixels as RGBA, missing pixels are white
+    pub(crate) fn rgba_bytes(&self) -> Vec<u8> {
+        self.rgba_region(UVec2::new(0, 0), self.size)
+    }
+
+    pub(crate) fn rgba_region(&self, position: UVec2, size: UVec2) -> Vec<u8> {
+        let channels = self.image_format.channels();
+        let mut bytes = Vec::with_capacity((size.x * size.y * 4) as usize);
+
+        for y in 0..size.y {
+            let start = ((position.y + y) * self.size.x + position.x) as usize * channels;
+            let end = (start + size.x as usize * channels).min(self.image.len());
+            let row = self.image.get(start..end).unwrap_or(&[]);
+
+            match self.image_format {
+                FigureImageFormat::Rgba => bytes.extend_from_slice(row),
+                FigureImageFormat::Rgb => bytes.extend(
+                    row.chunks_exact(3)
+                        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255]),
+                ),
+            }
+            bytes.resize(((y + 1) * size.x * 4) as usize, 255);
+        }
+
+        bytes
+    }
+}
+
+impl FigureImageFormat {
+    fn channels(&self) -> usize {
+        match self {
+            FigureImageFormat::Rgb => 3,
+            FigureImageFormat::Rgba => 4,
+        }
+    }
+}
+
+// Doesn't keep the image alive, used to free images nobody uses anymore
+#[derive(Clone)]
+pub(crate) struct WeakFigureImage {
+    size: UVec2,
+    image: Weak<Vec<u8>>,
+    image_format: FigureImageFormat,
+    shared: Weak<SharedImageState>,
+}
+
+impl WeakFigureImage {
+    pub(crate) fn upgrade(&self) -> Option<FigureImage> {
+        Some(FigureImage {
+            size: self.size,
+            image: self.image.upgrade()?,
+            image_format: self.image_format.clone(),
+            shared: self.shared.upgrade()?,
+        })
+    }
+
+    pub(crate) fn is_alive(&self) -> bool {
+        self.shared.strong_count() > 0
+    }
 }
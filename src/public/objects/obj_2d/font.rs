@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use fontdue::{Font, FontSettings};
+
+// Font loaded with Context::load_font_from_path or Context::load_font_from_bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FontHandle {
+    index: usize,
+}
+
+impl FontHandle {
+    // The bundled fonts are loaded first, in this order
+    #[cfg(feature = "bundled-fonts")]
+    pub const ROBOTO: FontHandle = FontHandle { index: 0 };
+    #[cfg(feature = "bundled-fonts")]
+    pub const COMIC_SANS: FontHandle = FontHandle { index: 1 };
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    // Message of the font parser
+    Parse(&'static str),
+    EmptyCollection,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "failed to read font: {}", error),
+            FontError::Parse(message) => write!(f, "failed to parse font: {}", message),
+            FontError::EmptyCollection => write!(f, "font collection contains no fonts"),
+        }
+    }
+}
+
+impl Error for FontError {}
+
+impl From<io::Error> for FontError {
+    fn from(error: io::Error) -> Self {
+        FontError::Io(error)
+    }
+}
+
+pub(crate) struct FontRegistry {
+    fonts: Vec<Font>,
+    // Loading a file again returns the fonts that were loaded from it before
+    paths: HashMap<PathBuf, Vec<FontHandle>>,
+}
+
+impl FontRegistry {
+    pub(crate) fn new() -> Self {
+        #[cfg_attr(not(feature = "bundled-fonts"), allow(unused_mut))]
+        let mut registry = Self {
+            fonts: Vec::new(),
+            paths: HashMap::new(),
+        };
+
+        #[cfg(feature = "bundled-fonts")]
+        {
+            registry
+                .load_bytes(include_bytes!(
+                    "../../../../assets/fonts/Roboto/Roboto-Regular.ttf"
+                ))
+                .unwrap();
+            registry
+                .load_bytes(include_bytes!(
+                    "../../../../assets/fonts/comic-sans-ms/comicz.ttf"
+                ))
+                .unwrap();
+        }
+
+        registry
+    }
+
+    // A collection loaded from the path returns its first font
+    pub(crate) fn load_path(&mut self, path: &Path) -> Result<FontHandle, FontError> {
+        self.load_collection_path(path)?
+            .first()
+            .copied()
+            .ok_or(FontError::EmptyCollection)
+    }
+
+    pub(crate) fn load_collection_path(
+        &mut self,
+        path: &Path,
+    ) -> Result<Vec<FontHandle>, FontError> {
+        if let Some(handles) = self.paths.get(path) {
+            return Ok(handles.clone());
+        }
+
+        let handles = self.load_collection_bytes(&fs::read(path)?)?;
+        self.paths.insert(path.to_path_buf(), handles.clone());
+        Ok(handles)
+    }
+
+    pub(crate) fn load_bytes(&mut self, bytes: &[u8]) -> Result<FontHandle, FontError> {
+        let font = parse_font(bytes, 0)?;
+        Ok(self.push(font))
+    }
+
+    // Loads every font of a collection, or the only font of a regular font file
+    pub(crate) fn load_collection_bytes(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<Vec<FontHandle>, FontError> {
+        // Everything is parsed first, so nothing is loaded when one of the fonts is invalid
+        let fonts = (0..face_count(bytes))
+            .map(|index| parse_font(bytes, index))
+            .collect::<Result<Vec<_>, _>>()?;
+        if fonts.is_empty() {
+            return Err(FontError::EmptyCollection);
+        }
+        Ok(fonts.into_iter().map(|font| self.push(font)).collect())
+    }
+
+    fn push(&mut self, font: Font) -> FontHandle {
+        self.fonts.push(font);
+        FontHandle {
+            index: self.fonts.len() - 1,
+        }
+    }
+
+    // Text without a font uses the first font that was loaded
+    pub(crate) fn get(&self, handle: Option<FontHandle>) -> Option<(FontHandle, &Font)> {
+        let handle = handle.unwrap_or(FontHandle { index: 0 });
+        self.fonts.get(handle.index).map(|font| (handle, font))
+    }
+}
+
+fn parse_font(bytes: &[u8], index: u32) -> Result<Font, FontError> {
+    Font::from_bytes(
+        bytes,
+        FontSettings {
+            collection_index: index,
+            ..Default::default()
+        },
+    )
+    .map_err(FontError::Parse)
+}
+
+// Font collections start with a ttcf tag, followed by the version and the number of fonts
+fn face_count(bytes: &[u8]) -> u32 {
+    match bytes.get(0..12) {
+        Some(header) if &header[0..4] == b"ttcf" => {
+            u32::from_be_bytes([header[8], header[9], header[10], header[11]])
+        }
+        _ => 1,
+    }
+}
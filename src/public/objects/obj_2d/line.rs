@@ -1,6 +1,20 @@
 use nalgebra_glm::{Vec2, Vec4};
 
-use crate::public::objects::DEFAULT_COLOR;
+use crate::public::objects::{StrokeStyle, DEFAULT_COLOR};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
 
 #[derive(Clone, Debug)]
 pub struct Line {
@@ -8,6 +22,8 @@ pub struct Line {
     pub p0: Vec2,
     pub p1: Vec2,
     pub thickness: u32,
+    pub cap: LineCap,
+    pub style: StrokeStyle,
 }
 
 impl Line {
@@ -17,6 +33,8 @@ impl Line {
             p0,
             p1,
             thickness: 1,
+            cap: LineCap::Butt,
+            style: StrokeStyle::new_solid(),
         }
     }
 
@@ -29,4 +47,14 @@ impl Line {
         self.thickness = thickness;
         self
     }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_style(mut self, style: StrokeStyle) -> Self {
+        self.style = style;
+        self
+    }
 }
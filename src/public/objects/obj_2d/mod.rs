@@ -1,16 +1,32 @@
 use nalgebra_glm::Vec2;
 
-use self::text::TextFont;
+use self::anchor::Anchor;
 
+pub mod anchor;
+pub mod animation;
+pub mod arc;
 pub mod circle;
+pub mod ellipse;
 pub mod figure;
+pub mod font;
 pub mod line;
+pub mod nine_slice;
+pub mod particles;
+pub mod path;
+pub mod pie;
+pub mod points;
 pub mod polygon;
+pub mod polyline;
 pub mod rect;
+pub mod ring;
+pub mod sprite_sheet;
 pub mod text;
+pub mod texture;
+pub mod texture_atlas;
+pub mod tilemap;
 
 const DEFAULT_POSITION_2D: Vec2 = Vec2::new(50., 50.);
 const DEFAULT_SIZE_2D: Vec2 = Vec2::new(20., 20.);
-#[allow(unused)]
-const DEFAULT_ROTATION_2D: Vec2 = Vec2::new(0., 0.);
-const DEFAULT_FONT: TextFont = TextFont::Roboto;
+const DEFAULT_ROTATION_2D: f32 = 0.;
+const DEFAULT_FONT_SIZE: f32 = 17.;
+const DEFAULT_ANCHOR: Anchor = Anchor::Center;
@@ -0,0 +1,109 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+use super::{
+    figure::{FigureImage, FilterMode, SourceRect},
+    texture::TextureHandle,
+    DEFAULT_POSITION_2D,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceMode {
+    Stretch,
+    // Repeats the part at its original size, the last repetition is cut off
+    Tile,
+}
+
+// Image split into a 3x3 grid by the insets, for panels and buttons of any size.
+// The corners keep their size, the edges and the centre stretch or tile to fill the rest.
+// Corners are only scaled down when the size is smaller than the insets together.
+#[derive(Clone)]
+pub struct NineSlice {
+    pub image: FigureImage,
+    // Part of the image that is sliced, the whole image when None
+    pub source: Option<SourceRect>,
+    pub position: Vec2,
+    pub size: Vec2,
+    // Insets in image pixels, measured from the edges of the source
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    pub edge_mode: SliceMode,
+    pub center_mode: SliceMode,
+    pub filter: FilterMode,
+    pub tint: Vec4,
+}
+
+impl NineSlice {
+    pub fn new(image: FigureImage, left: f32, right: f32, top: f32, bottom: f32) -> Self {
+        let size = Vec2::new(image.size.x as f32, image.size.y as f32);
+        Self {
+            image,
+            source: None,
+            position: DEFAULT_POSITION_2D,
+            size,
+            left,
+            right,
+            top,
+            bottom,
+            edge_mode: SliceMode::Stretch,
+            center_mode: SliceMode::Stretch,
+            filter: FilterMode::Nearest,
+            tint: Vec4::new(1., 1., 1., 1.),
+        }
+    }
+
+    pub fn new_with_texture(
+        texture: &TextureHandle,
+        left: f32,
+        right: f32,
+        top: f32,
+        bottom: f32,
+    ) -> Self {
+        Self::new(texture.image().clone(), left, right, top, bottom)
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceRect) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_edge_mode(mut self, edge_mode: SliceMode) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    pub fn with_center_mode(mut self, center_mode: SliceMode) -> Self {
+        self.center_mode = center_mode;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_tint(mut self, tint: Vec4) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub(crate) fn source_rect(&self) -> SourceRect {
+        self.source.unwrap_or_else(|| {
+            SourceRect::new(
+                Vec2::new(0., 0.),
+                Vec2::new(self.image.size.x as f32, self.image.size.y as f32),
+            )
+        })
+    }
+}
@@ -0,0 +1,396 @@
+use std::f32::consts::TAU;
+
+use nalgebra_glm::{self as glm, TVec, Vec2, Vec4};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::rendering::geometry::angle_to_vec;
+
+use super::figure::Figure;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EmitterShape {
+    Point,
+    // Spawns anywhere within the radius
+    Circle(f32),
+    // Spawns anywhere within the size, centered on the emitter
+    Rect(Vec2),
+    // Spawns on the emitter and moves towards direction, give or take half the spread, as fast
+    // as the random velocity. Angles are in radians, clockwise from the x axis.
+    Cone { direction: f32, spread: f32 },
+}
+
+#[derive(Clone)]
+pub enum ParticleKind {
+    Circle,
+    Rect,
+    // Draws the image of the figure with its source, flips, sampling and tint.
+    // The size comes from the emitter.
+    Figure(Figure),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    Alpha,
+    // Overlapping particles add up to brighter colors, for fire, sparks and magic
+    Additive,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub acceleration: Vec2,
+    pub rotation: f32,
+    pub angular_velocity: f32,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+// Spawns, moves and removes particles. Context::particles updates the emitter with the
+// frame time and draws all its particles at once.
+// Ranges are (min, max) and picked from randomly for every particle.
+#[derive(Clone)]
+pub struct ParticleEmitter {
+    pub position: Vec2,
+    pub shape: EmitterShape,
+    pub kind: ParticleKind,
+    pub blend: BlendMode,
+    // Particles per second
+    pub spawn_rate: f32,
+    pub lifetime: (f32, f32),
+    pub velocity: (Vec2, Vec2),
+    pub acceleration: (Vec2, Vec2),
+    pub rotation: (f32, f32),
+    pub angular_velocity: (f32, f32),
+    // Spread evenly over the lifetime of a particle and blended in between
+    pub colors: Vec<Vec4>,
+    pub sizes: Vec<Vec2>,
+    pub max_particles: usize,
+    // Spawning stops after this many seconds, bursts still go off
+    pub duration: Option<f32>,
+    pub emitting: bool,
+    particles: Vec<Particle>,
+    // Time since the start and the amount of particles to spawn at that time
+    bursts: Vec<(f32, u32)>,
+    time: f32,
+    // Fraction of a particle left over from the previous update
+    spawn_remainder: f32,
+    seed: Option<u64>,
+    rng: StdRng,
+}
+
+impl ParticleEmitter {
+    pub fn new_default() -> Self {
+        Self {
+            position: Vec2::new(0., 0.),
+            shape: EmitterShape::Point,
+            kind: ParticleKind::Circle,
+            blend: BlendMode::Alpha,
+            spawn_rate: 10.,
+            lifetime: (1., 1.),
+            velocity: (Vec2::new(-50., -50.), Vec2::new(50., 50.)),
+            acceleration: (Vec2::new(0., 0.), Vec2::new(0., 0.)),
+            rotation: (0., 0.),
+            angular_velocity: (0., 0.),
+            colors: vec![Vec4::new(1., 1., 1., 1.), Vec4::new(1., 1., 1., 0.)],
+            sizes: vec![Vec2::new(8., 8.)],
+            max_particles: 1000,
+            duration: None,
+            emitting: true,
+            particles: Vec::new(),
+            bursts: Vec::new(),
+            time: 0.,
+            spawn_remainder: 0.,
+            seed: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: EmitterShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: ParticleKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn with_spawn_rate(mut self, spawn_rate: f32) -> Self {
+        self.spawn_rate = spawn_rate;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min: f32, max: f32) -> Self {
+        self.lifetime = (min, max);
+        self
+    }
+
+    pub fn with_velocity(mut self, min: Vec2, max: Vec2) -> Self {
+        self.velocity = (min, max);
+        self
+    }
+
+    pub fn with_acceleration(mut self, min: Vec2, max: Vec2) -> Self {
+        self.acceleration = (min, max);
+        self
+    }
+
+    pub fn with_rotation(mut self, min: f32, max: f32) -> Self {
+        self.rotation = (min, max);
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, min: f32, max: f32) -> Self {
+        self.angular_velocity = (min, max);
+        self
+    }
+
+    pub fn with_colors(mut self, colors: Vec<Vec4>) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    pub fn with_sizes(mut self, sizes: Vec<Vec2>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: usize) -> Self {
+        self.max_particles = max_particles;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    // Spawns count particles at once, time seconds after the start
+    pub fn with_burst(mut self, time: f32, count: u32) -> Self {
+        self.bursts.push((time, count));
+        self
+    }
+
+    // Emitters with the same seed and settings that are updated with the same
+    // frame times produce the same particles
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    // Spawns count particles right away
+    pub fn burst(&mut self, count: u32) {
+        for _ in 0..count {
+            self.spawn();
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.age += dt;
+            particle.velocity += particle.acceleration * dt;
+            particle.position += particle.velocity * dt;
+            particle.rotation += particle.angular_velocity * dt;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+
+        let spawning = self.emitting && self.duration.map_or(true, |duration| self.time < duration);
+        if spawning {
+            let count = self.spawn_rate * dt + self.spawn_remainder;
+            self.spawn_remainder = count.fract();
+            self.burst(count as u32);
+        }
+
+        let (start, end) = (self.time, self.time + dt);
+        let bursts = self
+            .bursts
+            .iter()
+            .filter(|(time, _)| start <= *time && *time < end)
+            .map(|(_, count)| count)
+            .sum();
+        self.burst(bursts);
+
+        self.time = end;
+    }
+
+    // Removes all particles and starts over, scheduled bursts go off again
+    pub fn reset(&mut self) {
+        self.particles.clear();
+        self.time = 0.;
+        self.spawn_remainder = 0.;
+        if let Some(seed) = self.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    // True when no particles are left and none will be spawned anymore
+    pub fn is_finished(&self) -> bool {
+        let spawning = self.emitting
+            && self.spawn_rate > 0.
+            && self.duration.map_or(true, |duration| self.time < duration);
+        let bursts_left = self.bursts.iter().any(|(time, _)| *time >= self.time);
+
+        self.particles.is_empty() && !spawning && !bursts_left
+    }
+
+    pub(crate) fn color(&self, particle: &Particle) -> Vec4 {
+        over_life(&self.colors, particle).unwrap_or(Vec4::new(1., 1., 1., 1.))
+    }
+
+    pub(crate) fn size(&self, particle: &Particle) -> Vec2 {
+        over_life(&self.sizes, particle).unwrap_or(Vec2::new(0., 0.))
+    }
+
+    fn spawn(&mut self) {
+        if self.particles.len() >= self.max_particles {
+            return;
+        }
+
+        let offset = match self.shape {
+            EmitterShape::Point | EmitterShape::Cone { .. } => Vec2::new(0., 0.),
+            EmitterShape::Circle(radius) => {
+                // The square root spreads the particles evenly over the area
+                let distance = radius * self.rng.gen::<f32>().sqrt();
+                glm::rotate_vec2(&Vec2::new(distance, 0.), self.rng.gen::<f32>() * TAU)
+            }
+            EmitterShape::Rect(size) => {
+                let t = Vec2::new(self.rng.gen(), self.rng.gen());
+                (t - Vec2::new(0.5, 0.5)).component_mul(&size)
+            }
+        };
+
+        let mut velocity = random_vec2(&mut self.rng, self.velocity);
+        if let EmitterShape::Cone { direction, spread } = self.shape {
+            let angle = direction + spread * (self.rng.gen::<f32>() - 0.5);
+            velocity = angle_to_vec(angle) * velocity.norm();
+        }
+
+        let particle = Particle {
+            position: self.position + offset,
+            velocity,
+            acceleration: random_vec2(&mut self.rng, self.acceleration),
+            rotation: random_f32(&mut self.rng, self.rotation),
+            angular_velocity: random_f32(&mut self.rng, self.angular_velocity),
+            age: 0.,
+            lifetime: random_f32(&mut self.rng, self.lifetime),
+        };
+        self.particles.push(particle);
+    }
+}
+
+fn random_f32(rng: &mut StdRng, (min, max): (f32, f32)) -> f32 {
+    min + (max - min) * rng.gen::<f32>()
+}
+
+fn random_vec2(rng: &mut StdRng, (min, max): (Vec2, Vec2)) -> Vec2 {
+    Vec2::new(
+        random_f32(rng, (min.x, max.x)),
+        random_f32(rng, (min.y, max.y)),
+    )
+}
+
+fn over_life<const D: usize>(keys: &[TVec<f32, D>], particle: &Particle) -> Option<TVec<f32, D>> {
+    if keys.len() < 2 {
+        return keys.first().copied();
+    }
+
+    let t = (particle.age / particle.lifetime).clamp(0., 1.) * (keys.len() - 1) as f32;
+    let index = (t as usize).min(keys.len() - 2);
+    Some(keys[index].lerp(&keys[index + 1], t - index as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::Vec2;
+
+    use super::{EmitterShape, ParticleEmitter};
+
+    const DTS: [f32; 6] = [0.016, 0.1, 0.033, 0.5, 0.016, 0.25];
+
+    fn emitter(seed: u64) -> ParticleEmitter {
+        ParticleEmitter::new_default()
+            .with_spawn_rate(40.)
+            .with_lifetime(0.5, 2.)
+            .with_shape(EmitterShape::Circle(20.))
+            .with_burst(0.2, 15)
+            .with_seed(seed)
+    }
+
+    #[test]
+    fn same_seed_gives_same_particles() {
+        let mut a = emitter(7);
+        let mut b = emitter(7);
+        for dt in DTS {
+            a.update(dt);
+            b.update(dt);
+            assert_eq!(a.particles(), b.particles());
+        }
+        assert!(!a.particles().is_empty());
+
+        let mut c = emitter(8);
+        for dt in DTS {
+            c.update(dt);
+        }
+        assert_ne!(a.particles(), c.particles());
+    }
+
+    #[test]
+    fn reset_starts_over_with_the_seed() {
+        let mut a = emitter(3);
+        for dt in DTS {
+            a.update(dt);
+        }
+        let particles = a.particles().to_vec();
+
+        a.reset();
+        for dt in DTS {
+            a.update(dt);
+        }
+        assert_eq!(a.particles(), particles.as_slice());
+    }
+
+    #[test]
+    fn cone_points_velocity_within_spread() {
+        let (direction, spread) = (1., 0.5);
+        let mut emitter = ParticleEmitter::new_default()
+            .with_shape(EmitterShape::Cone { direction, spread })
+            .with_velocity(Vec2::new(-50., -50.), Vec2::new(50., 50.))
+            .with_seed(1);
+        emitter.burst(200);
+
+        for particle in emitter.particles() {
+            let angle = particle.velocity.y.atan2(particle.velocity.x);
+            assert!((angle - direction).abs() <= spread * 0.5 + 0.0001);
+            assert_eq!(particle.position, Vec2::new(0., 0.));
+        }
+    }
+
+    #[test]
+    fn spawn_rate_carries_over_fractions() {
+        let mut emitter = ParticleEmitter::new_default()
+            .with_spawn_rate(10.)
+            .with_lifetime(10., 10.)
+            .with_seed(1);
+        for _ in 0..10 {
+            emitter.update(0.05);
+        }
+        assert_eq!(emitter.particles().len(), 5);
+    }
+}
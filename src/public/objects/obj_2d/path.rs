@@ -0,0 +1,132 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::public::objects::{fill::Fill, StrokeStyle, DEFAULT_COLOR};
+
+use super::{
+    line::{LineCap, LineJoin},
+    polygon::FillRule,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadTo(Vec2, Vec2),
+    CubicTo(Vec2, Vec2, Vec2),
+    // Arc with the given radius touching the lines from the current point to the first point
+    // and from the first point to the second point, like arcTo in the HTML canvas
+    ArcTo(Vec2, Vec2, f32),
+    Close,
+}
+
+#[derive(Clone, Debug)]
+pub struct PathStroke {
+    pub color: Vec4,
+    pub thickness: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub style: StrokeStyle,
+}
+
+impl PathStroke {
+    pub fn new(color: Vec4, thickness: f32) -> Self {
+        Self {
+            color,
+            thickness,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            style: StrokeStyle::new_solid(),
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_style(mut self, style: StrokeStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Path {
+    pub commands: Vec<PathCommand>,
+    pub fill: Option<Fill>,
+    pub fill_rule: FillRule,
+    pub stroke: Option<PathStroke>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            fill: Some(Fill::Solid(DEFAULT_COLOR)),
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+        }
+    }
+
+    pub fn move_to(mut self, point: Vec2) -> Self {
+        self.commands.push(PathCommand::MoveTo(point));
+        self
+    }
+
+    pub fn line_to(mut self, point: Vec2) -> Self {
+        self.commands.push(PathCommand::LineTo(point));
+        self
+    }
+
+    pub fn quad_to(mut self, control: Vec2, point: Vec2) -> Self {
+        self.commands.push(PathCommand::QuadTo(control, point));
+        self
+    }
+
+    pub fn cubic_to(mut self, control_0: Vec2, control_1: Vec2, point: Vec2) -> Self {
+        self.commands
+            .push(PathCommand::CubicTo(control_0, control_1, point));
+        self
+    }
+
+    pub fn arc_to(mut self, p0: Vec2, p1: Vec2, radius: f32) -> Self {
+        self.commands.push(PathCommand::ArcTo(p0, p1, radius));
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    pub fn with_fill<T: Into<Fill>>(mut self, fill: T) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
+    pub fn without_fill(mut self) -> Self {
+        self.fill = None;
+        self
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    pub fn with_stroke(mut self, stroke: PathStroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,58 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::public::objects::DEFAULT_COLOR;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointShape {
+    Square,
+    Round,
+}
+
+// Many points of the same size drawn at once, for scatter plots, star fields and pixels.
+// In the UI a square point of size 1 at a pixel centre, like (10.5, 20.5), fills that pixel.
+#[derive(Clone, Debug)]
+pub struct Points {
+    pub positions: Vec<Vec2>,
+    pub color: Vec4,
+    // Overrides color per point, points without a color fall back to color
+    pub colors: Option<Vec<Vec4>>,
+    pub size: f32,
+    pub shape: PointShape,
+}
+
+impl Points {
+    pub fn new_with_positions(positions: Vec<Vec2>) -> Self {
+        Self {
+            positions,
+            color: DEFAULT_COLOR,
+            colors: None,
+            size: 1.,
+            shape: PointShape::Square,
+        }
+    }
+
+    pub fn with_positions(mut self, positions: Vec<Vec2>) -> Self {
+        self.positions = positions;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_colors(mut self, colors: Vec<Vec4>) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: PointShape) -> Self {
+        self.shape = shape;
+        self
+    }
+}
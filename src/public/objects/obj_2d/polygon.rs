@@ -1,11 +1,64 @@
+use std::{error::Error, fmt};
+
 use nalgebra_glm::{Vec2, Vec4};
 
-use crate::public::objects::{Border, DEFAULT_COLOR};
+use crate::{
+    public::objects::{fill::Fill, Border, DEFAULT_COLOR},
+    rendering::geometry::triangulation::signed_area,
+};
+
+const MIN_AREA: f32 = 0.0001;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolygonError {
+    // hole is None for the outer contour
+    TooFewPoints { hole: Option<usize> },
+    NonFinitePoint { hole: Option<usize>, index: usize },
+    ZeroArea { hole: Option<usize> },
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let contour_name = |hole: &Option<usize>| match hole {
+            Some(hole) => format!("hole {}", hole),
+            None => "outer contour".to_string(),
+        };
+
+        match self {
+            PolygonError::TooFewPoints { hole } => {
+                write!(f, "{} has less than 3 points", contour_name(hole))
+            }
+            PolygonError::NonFinitePoint { hole, index } => {
+                write!(
+                    f,
+                    "{} has a non finite point at index {}",
+                    contour_name(hole),
+                    index
+                )
+            }
+            PolygonError::ZeroArea { hole } => {
+                write!(f, "{} has no area", contour_name(hole))
+            }
+        }
+    }
+}
+
+impl Error for PolygonError {}
 
 #[derive(Clone, Debug)]
 pub struct Polygon {
     pub color: Vec4,
+    // Replaces color when set
+    pub fill: Option<Fill>,
     pub points: Vec<Vec2>,
+    pub holes: Vec<Vec<Vec2>>,
+    pub fill_rule: FillRule,
     pub border: Option<Border>,
 }
 
@@ -13,17 +66,16 @@ impl Polygon {
     pub fn new_with_points(points: Vec<Vec2>) -> Self {
         Self {
             color: DEFAULT_COLOR,
+            fill: None,
             points,
+            holes: Vec::new(),
+            fill_rule: FillRule::NonZero,
             border: None,
         }
     }
 
     pub fn new_triangle(points: [Vec2; 3]) -> Self {
-        Self {
-            color: DEFAULT_COLOR,
-            points: points.into(),
-            border: None,
-        }
+        Self::new_with_points(points.into())
     }
 
     pub fn with_color(mut self, color: Vec4) -> Self {
@@ -31,8 +83,137 @@ impl Polygon {
         self
     }
 
+    pub fn with_fill<T: Into<Fill>>(mut self, fill: T) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
+    pub fn with_hole(mut self, points: Vec<Vec2>) -> Self {
+        self.holes.push(points);
+        self
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
     pub fn with_border(mut self, border: Border) -> Self {
         self.border = Some(border);
         self
     }
+
+    pub fn validate(&self) -> Result<(), PolygonError> {
+        validate_contour(&self.points, None)?;
+        for (i, hole) in self.holes.iter().enumerate() {
+            validate_contour(hole, Some(i))?;
+        }
+        Ok(())
+    }
+
+    // Holes are wound opposite to the outer contour so they are cut out with either fill rule.
+    // Repeated points are left out, like a last point that closes the contour again.
+    pub(crate) fn contours(&self) -> Vec<Vec<Vec2>> {
+        let outer_area = signed_area(&self.points);
+        let mut contours = vec![without_repeated_points(&self.points)];
+        for hole in &self.holes {
+            let mut hole = without_repeated_points(hole);
+            if signed_area(&hole).signum() == outer_area.signum() {
+                hole.reverse();
+            }
+            contours.push(hole);
+        }
+        contours
+    }
+}
+
+fn validate_contour(points: &[Vec2], hole: Option<usize>) -> Result<(), PolygonError> {
+    if points.len() < 3 {
+        return Err(PolygonError::TooFewPoints { hole });
+    }
+    if let Some(index) = points
+        .iter()
+        .position(|p| !p.x.is_finite() || !p.y.is_finite())
+    {
+        return Err(PolygonError::NonFinitePoint { hole, index });
+    }
+    if signed_area(points).abs() < MIN_AREA {
+        return Err(PolygonError::ZeroArea { hole });
+    }
+    Ok(())
+}
+
+fn without_repeated_points(points: &[Vec2]) -> Vec<Vec2> {
+    let mut unique: Vec<Vec2> = Vec::with_capacity(points.len());
+    for point in points {
+        if unique.last() != Some(point) {
+            unique.push(*point);
+        }
+    }
+    while unique.len() > 1 && unique.first() == unique.last() {
+        unique.pop();
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::Vec2;
+
+    use super::{Polygon, PolygonError};
+
+    fn points(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    #[test]
+    fn accepts_valid_polygon() {
+        let polygon = Polygon::new_with_points(points(&[(0., 0.), (10., 0.), (10., 10.)]))
+            .with_hole(points(&[(6., 2.), (8., 2.), (8., 4.)]));
+        assert_eq!(polygon.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_too_few_points() {
+        let polygon = Polygon::new_with_points(points(&[(0., 0.), (10., 0.)]));
+        assert_eq!(
+            polygon.validate(),
+            Err(PolygonError::TooFewPoints { hole: None })
+        );
+    }
+
+    #[test]
+    fn rejects_zero_area() {
+        let polygon = Polygon::new_with_points(points(&[(0., 0.), (5., 5.), (10., 10.)]));
+        assert_eq!(
+            polygon.validate(),
+            Err(PolygonError::ZeroArea { hole: None })
+        );
+    }
+
+    #[test]
+    fn accepts_repeated_points() {
+        let polygon = Polygon::new_with_points(points(&[
+            (0., 0.),
+            (10., 0.),
+            (10., 0.),
+            (10., 10.),
+            (0., 0.),
+        ]));
+        assert_eq!(polygon.validate(), Ok(()));
+        assert_eq!(
+            polygon.contours(),
+            vec![points(&[(0., 0.), (10., 0.), (10., 10.)])]
+        );
+    }
+
+    #[test]
+    fn rejects_degenerate_hole() {
+        let polygon = Polygon::new_with_points(points(&[(0., 0.), (10., 0.), (10., 10.)]))
+            .with_hole(points(&[(1., 1.), (2., 1.)]));
+        assert_eq!(
+            polygon.validate(),
+            Err(PolygonError::TooFewPoints { hole: Some(0) })
+        );
+    }
 }
@@ -0,0 +1,66 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::public::objects::DEFAULT_COLOR;
+
+use super::line::{LineCap, LineJoin};
+
+#[derive(Clone, Debug)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub color: Vec4,
+    // Overrides color per point, points without a color fall back to color
+    pub vertex_colors: Option<Vec<Vec4>>,
+    pub thickness: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub closed: bool,
+}
+
+impl Polyline {
+    pub fn new_with_points(points: Vec<Vec2>) -> Self {
+        Self {
+            points,
+            color: DEFAULT_COLOR,
+            vertex_colors: None,
+            thickness: 1.,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            closed: false,
+        }
+    }
+
+    pub fn with_points(mut self, points: Vec<Vec2>) -> Self {
+        self.points = points;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_vertex_colors(mut self, vertex_colors: Vec<Vec4>) -> Self {
+        self.vertex_colors = Some(vertex_colors);
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+}
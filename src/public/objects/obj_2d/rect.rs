@@ -1,24 +1,44 @@
 use nalgebra_glm::{Vec2, Vec4};
 
-use crate::public::objects::{Border, DEFAULT_COLOR};
+use crate::public::objects::{
+    fill::Fill,
+    shadow::{Glow, Shadow},
+    Border, DEFAULT_COLOR,
+};
 
-use super::{DEFAULT_POSITION_2D, DEFAULT_SIZE_2D};
+use super::{
+    anchor::Anchor, DEFAULT_ANCHOR, DEFAULT_POSITION_2D, DEFAULT_ROTATION_2D, DEFAULT_SIZE_2D,
+};
 
 #[derive(Clone, Debug)]
 pub struct Rect {
     pub position: Vec2,
     pub size: Vec2,
     pub color: Vec4,
+    // Replaces color when set
+    pub fill: Option<Fill>,
     pub border: Option<Border>,
+    pub shadow: Option<Shadow>,
+    pub glow: Option<Glow>,
+    pub rotation: f32,
+    // Radii in the order top left, top right, bottom right, bottom left
+    pub corner_radii: Vec4,
+    pub anchor: Anchor,
 }
 
 impl Rect {
     pub fn new_default() -> Self {
         Self {
             color: DEFAULT_COLOR,
+            fill: None,
             position: DEFAULT_POSITION_2D,
             size: DEFAULT_SIZE_2D,
             border: None,
+            shadow: None,
+            glow: None,
+            rotation: DEFAULT_ROTATION_2D,
+            corner_radii: Vec4::new(0., 0., 0., 0.),
+            anchor: DEFAULT_ANCHOR,
         }
     }
 
@@ -27,6 +47,11 @@ impl Rect {
         self
     }
 
+    pub fn with_fill<T: Into<Fill>>(mut self, fill: T) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
     pub fn with_position(mut self, position: Vec2) -> Self {
         self.position = position;
         self
@@ -41,4 +66,40 @@ impl Rect {
         self.border = Some(border);
         self
     }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radii = Vec4::new(radius, radius, radius, radius);
+        self
+    }
+
+    pub fn with_corner_radii(
+        mut self,
+        top_left: f32,
+        top_right: f32,
+        bottom_right: f32,
+        bottom_left: f32,
+    ) -> Self {
+        self.corner_radii = Vec4::new(top_left, top_right, bottom_right, bottom_left);
+        self
+    }
+
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    pub fn with_glow(mut self, glow: Glow) -> Self {
+        self.glow = Some(glow);
+        self
+    }
 }
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use nalgebra_glm::{UVec2, Vec2};
+
+use super::figure::{Figure, FigureImage, SourceRect};
+
+#[derive(Clone)]
+pub struct SpriteSheet {
+    pub image: FigureImage,
+    frames: Vec<SourceRect>,
+    names: HashMap<String, usize>,
+}
+
+impl SpriteSheet {
+    // Sheet without frames, add them with with_frame
+    pub fn new(image: FigureImage) -> Self {
+        Self {
+            image,
+            frames: Vec::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    // Slices the image into frames of frame_size, row by row starting at the top left
+    pub fn from_grid(image: FigureImage, frame_size: UVec2) -> Self {
+        Self::from_grid_with_spacing(image, frame_size, UVec2::new(0, 0), UVec2::new(0, 0))
+    }
+
+    // Like from_grid, for sheets with a margin around the image and spacing between frames
+    pub fn from_grid_with_spacing(
+        image: FigureImage,
+        frame_size: UVec2,
+        margin: UVec2,
+        spacing: UVec2,
+    ) -> Self {
+        let mut frames = Vec::new();
+
+        if frame_size.x > 0 && frame_size.y > 0 {
+            let step = frame_size + spacing;
+            let mut y = margin.y;
+            while y + frame_size.y <= image.size.y {
+                let mut x = margin.x;
+                while x + frame_size.x <= image.size.x {
+                    frames.push(SourceRect::new(
+                        Vec2::new(x as f32, y as f32),
+                        Vec2::new(frame_size.x as f32, frame_size.y as f32),
+                    ));
+                    x += step.x;
+                }
+                y += step.y;
+            }
+        }
+
+        Self {
+            image,
+            frames,
+            names: HashMap::new(),
+        }
+    }
+
+    // Adds a named frame at the end of the sheet
+    pub fn with_frame(mut self, name: &str, source: SourceRect) -> Self {
+        self.names.insert(name.to_string(), self.frames.len());
+        self.frames.push(source);
+        self
+    }
+
+    // Gives an existing frame a name, for example to name frames sliced from a grid
+    pub fn with_name(mut self, name: &str, index: usize) -> Self {
+        self.names.insert(name.to_string(), index);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frame(&self, index: usize) -> Option<SourceRect> {
+        self.frames.get(index).copied()
+    }
+
+    pub fn frame_named(&self, name: &str) -> Option<SourceRect> {
+        self.index_of(name).and_then(|index| self.frame(index))
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    // Figure showing a single frame at its original size.
+    // Shows the whole image if the frame does not exist.
+    pub fn figure(&self, index: usize) -> Figure {
+        let figure = Figure::new_with_image(self.image.clone());
+        match self.frame(index) {
+            Some(source) => figure.with_size(source.size).with_source(source),
+            None => figure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{UVec2, Vec2};
+
+    use super::super::figure::{FigureImage, SourceRect};
+    use super::SpriteSheet;
+
+    fn frame(x: f32, y: f32, width: f32, height: f32) -> SourceRect {
+        SourceRect::new(Vec2::new(x, y), Vec2::new(width, height))
+    }
+
+    #[test]
+    fn slices_grid() {
+        let image = FigureImage::new_with_size(UVec2::new(64, 32));
+        let sheet = SpriteSheet::from_grid(image, UVec2::new(16, 16));
+        assert_eq!(sheet.len(), 8);
+        assert_eq!(sheet.frame(0), Some(frame(0., 0., 16., 16.)));
+        assert_eq!(sheet.frame(3), Some(frame(48., 0., 16., 16.)));
+        assert_eq!(sheet.frame(4), Some(frame(0., 16., 16., 16.)));
+        assert_eq!(sheet.frame(8), None);
+    }
+
+    #[test]
+    fn slices_grid_with_spacing() {
+        // 2 px margin and 1 px spacing, room for 3 by 2 frames of 10 px
+        let image = FigureImage::new_with_size(UVec2::new(36, 25));
+        let sheet = SpriteSheet::from_grid_with_spacing(
+            image,
+            UVec2::new(10, 10),
+            UVec2::new(2, 2),
+            UVec2::new(1, 1),
+        );
+        assert_eq!(sheet.len(), 6);
+        assert_eq!(sheet.frame(0), Some(frame(2., 2., 10., 10.)));
+        assert_eq!(sheet.frame(1), Some(frame(13., 2., 10., 10.)));
+        assert_eq!(sheet.frame(2), Some(frame(24., 2., 10., 10.)));
+        assert_eq!(sheet.frame(3), Some(frame(2., 13., 10., 10.)));
+        assert_eq!(sheet.frame(5), Some(frame(24., 13., 10., 10.)));
+    }
+
+    #[test]
+    fn skips_partial_and_empty_frames() {
+        let image = FigureImage::new_with_size(UVec2::new(20, 20));
+        let sheet = SpriteSheet::from_grid(image.clone(), UVec2::new(15, 15));
+        assert_eq!(sheet.len(), 1);
+
+        let sheet = SpriteSheet::from_grid(image, UVec2::new(0, 10));
+        assert!(sheet.is_empty());
+    }
+
+    #[test]
+    fn names_frames() {
+        let image = FigureImage::new_with_size(UVec2::new(32, 16));
+        let sheet = SpriteSheet::from_grid(image, UVec2::new(16, 16))
+            .with_name("jump", 1)
+            .with_frame("extra", frame(0., 0., 4., 4.));
+        assert_eq!(sheet.index_of("jump"), Some(1));
+        assert_eq!(sheet.frame_named("jump"), Some(frame(16., 0., 16., 16.)));
+        assert_eq!(sheet.index_of("extra"), Some(2));
+        assert_eq!(sheet.frame_named("missing"), None);
+    }
+}
@@ -1,21 +1,23 @@
 use nalgebra_glm::{Vec2, Vec4};
 
-use crate::public::objects::DEFAULT_COLOR;
+use crate::public::objects::{
+    shadow::{Glow, Shadow},
+    DEFAULT_COLOR,
+};
 
-use super::{DEFAULT_POSITION_2D, DEFAULT_FONT};
-
-#[derive(Clone, Debug)]
-pub enum TextFont {
-    ComicSans,
-    Roboto
-}
+use super::{font::FontHandle, DEFAULT_FONT_SIZE, DEFAULT_POSITION_2D};
 
 #[derive(Clone, Debug)]
 pub struct Text {
     pub text: String,
     pub color: Vec4,
     pub position: Vec2,
-    pub font: TextFont,
+    // The first loaded font is used when this is None
+    pub font: Option<FontHandle>,
+    // Height of the font in pixels
+    pub size: f32,
+    pub shadow: Option<Shadow>,
+    pub glow: Option<Glow>,
 }
 
 impl Text {
@@ -24,7 +26,10 @@ impl Text {
             text: text.to_string(),
             color: DEFAULT_COLOR,
             position: DEFAULT_POSITION_2D,
-            font: DEFAULT_FONT,
+            font: None,
+            size: DEFAULT_FONT_SIZE,
+            shadow: None,
+            glow: None,
         }
     }
 
@@ -43,8 +48,23 @@ impl Text {
         self
     }
 
-    pub fn with_font(mut self, font: TextFont) -> Self {
-        self.font = font;
+    pub fn with_font(mut self, font: FontHandle) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    pub fn with_glow(mut self, glow: Glow) -> Self {
+        self.glow = Some(glow);
         self
     }
 }
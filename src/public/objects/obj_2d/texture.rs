@@ -0,0 +1,50 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use image::ImageResult;
+use nalgebra_glm::UVec2;
+
+use super::figure::{FigureImage, WeakFigureImage};
+
+// Image loaded with Context::load_texture. Loading the same file again returns the same image,
+// which is uploaded to the gpu once and freed after the last handle and figure using it are gone.
+#[derive(Clone)]
+pub struct TextureHandle {
+    image: FigureImage,
+}
+
+impl TextureHandle {
+    pub fn image(&self) -> &FigureImage {
+        &self.image
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.image.size
+    }
+}
+
+pub(crate) struct TextureCache {
+    textures: HashMap<PathBuf, WeakFigureImage>,
+}
+
+impl TextureCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            textures: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn load(&mut self, path: &Path) -> ImageResult<TextureHandle> {
+        self.textures.retain(|_, image| image.is_alive());
+
+        if let Some(image) = self.textures.get(path).and_then(|image| image.upgrade()) {
+            return Ok(TextureHandle { image });
+        }
+
+        let image = FigureImage::from_path(path)?;
+        self.textures.insert(path.to_path_buf(), image.downgrade());
+        Ok(TextureHandle { image })
+    }
+}
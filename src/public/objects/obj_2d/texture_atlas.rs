@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use nalgebra_glm::{UVec2, Vec2};
+
+use super::figure::{Figure, FigureImage, FigureImageFormat, SourceRect};
+
+const DEFAULT_INITIAL_PAGE_SIZE: UVec2 = UVec2::new(256, 256);
+const DEFAULT_MAX_PAGE_SIZE: UVec2 = UVec2::new(2048, 2048);
+// Space around every image, filled with its edge pixels so filtering doesn't bleed
+const PADDING: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasRegion {
+    pub page: usize,
+    pub source: SourceRect,
+}
+
+// Packs small images into a few big pages. Figures of the same page that are drawn
+// right after each other are drawn at once.
+#[derive(Clone)]
+pub struct TextureAtlas {
+    initial_page_size: UVec2,
+    max_page_size: UVec2,
+    pages: Vec<AtlasPage>,
+    regions: HashMap<String, AtlasRegion>,
+}
+
+impl TextureAtlas {
+    pub fn new_default() -> Self {
+        Self {
+            initial_page_size: DEFAULT_INITIAL_PAGE_SIZE,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
+            pages: Vec::new(),
+            regions: HashMap::new(),
+        }
+    }
+
+    // Pages start at the initial size and grow up to the max size before a new page is added
+    pub fn with_page_size(mut self, initial_size: UVec2, max_size: UVec2) -> Self {
+        self.max_page_size = max_size;
+        self.initial_page_size = initial_size.inf(&max_size);
+        self
+    }
+
+    // Adding an image under an existing name replaces the region the name points to
+    pub fn add(&mut self, name: &str, image: &FigureImage) -> AtlasRegion {
+        let size = image.size + UVec2::new(PADDING * 2, PADDING * 2);
+        let max_page_size = self.max_page_size;
+
+        // Images bigger than the max page size get a page of their own right away,
+        // so the other pages aren't grown for nothing
+        let oversized = size.x > max_page_size.x || size.y > max_page_size.y;
+        let allocation = if oversized {
+            None
+        } else {
+            self.pages.iter_mut().enumerate().find_map(|(index, page)| {
+                page.allocate(size, max_page_size)
+                    .map(|position| (index, position))
+            })
+        };
+
+        let (page, position) = match allocation {
+            Some(allocation) => allocation,
+            None => {
+                let page_size = UVec2::new(
+                    self.initial_page_size.x.max(size.x),
+                    self.initial_page_size.y.max(size.y),
+                );
+                let mut page = AtlasPage::new(page_size);
+                let position = page.find_space(size).unwrap();
+                self.pages.push(page);
+                (self.pages.len() - 1, position)
+            }
+        };
+
+        self.pages[page].write(position + UVec2::new(PADDING, PADDING), image);
+
+        let region = AtlasRegion {
+            page,
+            source: SourceRect::new(
+                Vec2::new((position.x + PADDING) as f32, (position.y + PADDING) as f32),
+                Vec2::new(image.size.x as f32, image.size.y as f32),
+            ),
+        };
+        self.regions.insert(name.to_string(), region);
+        region
+    }
+
+    pub fn region(&self, name: &str) -> Option<AtlasRegion> {
+        self.regions.get(name).copied()
+    }
+
+    pub fn page(&self, index: usize) -> Option<&FigureImage> {
+        self.pages.get(index).map(|page| &page.image)
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    // Figure showing the region at its original size
+    pub fn figure(&self, region: AtlasRegion) -> Figure {
+        Figure::new_with_image(self.pages[region.page].image.clone())
+            .with_size(region.source.size)
+            .with_source(region.source)
+    }
+
+    pub fn figure_named(&self, name: &str) -> Option<Figure> {
+        self.region(name).map(|region| self.figure(region))
+    }
+}
+
+// Row of images with the same maximum height
+#[derive(Clone)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    used_width: u32,
+}
+
+#[derive(Clone)]
+struct AtlasPage {
+    image: FigureImage,
+    shelves: Vec<Shelf>,
+}
+
+impl AtlasPage {
+    fn new(size: UVec2) -> Self {
+        let pixels = vec![0; (size.x * size.y * 4) as usize];
+        Self {
+            image: FigureImage::new(size, pixels, FigureImageFormat::Rgba),
+            shelves: Vec::new(),
+        }
+    }
+
+    // Finds a free spot for size, growing the page when needed
+    fn allocate(&mut self, size: UVec2, max_page_size: UVec2) -> Option<UVec2> {
+        // Growing keeps the shelves where they are, so it only helps if the image fits at the
+        // max size
+        if !self.fits(size, max_page_size) {
+            return None;
+        }
+
+        loop {
+            if let Some(position) = self.find_space(size) {
+                return Some(position);
+            }
+
+            let page_size = self.image.size;
+            if page_size.x >= max_page_size.x && page_size.y >= max_page_size.y {
+                return None;
+            }
+
+            // Grow the smallest side first to keep the page square-ish
+            let grown = if page_size.x <= page_size.y && page_size.x < max_page_size.x {
+                UVec2::new((page_size.x * 2).min(max_page_size.x), page_size.y)
+            } else if page_size.y < max_page_size.y {
+                UVec2::new(page_size.x, (page_size.y * 2).min(max_page_size.y))
+            } else {
+                UVec2::new((page_size.x * 2).min(max_page_size.x), page_size.y)
+            };
+            self.resize(grown);
+        }
+    }
+
+    fn fits(&self, size: UVec2, page_size: UVec2) -> bool {
+        let on_shelf = self
+            .shelves
+            .iter()
+            .any(|shelf| shelf.height >= size.y && shelf.used_width + size.x <= page_size.x);
+        on_shelf || (size.x <= page_size.x && self.next_shelf_y() + size.y <= page_size.y)
+    }
+
+    fn next_shelf_y(&self) -> u32 {
+        self.shelves
+            .last()
+            .map(|shelf| shelf.y + shelf.height)
+            .unwrap_or(0)
+    }
+
+    fn find_space(&mut self, size: UVec2) -> Option<UVec2> {
+        let page_size = self.image.size;
+
+        // Use the lowest shelf the image fits on to waste the least space
+        let best_shelf = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| shelf.height >= size.y && shelf.used_width + size.x <= page_size.x)
+            .min_by_key(|shelf| shelf.height);
+
+        if let Some(shelf) = best_shelf {
+            let position = UVec2::new(shelf.used_width, shelf.y);
+            shelf.used_width += size.x;
+            return Some(position);
+        }
+
+        let y = self.next_shelf_y();
+        if size.x > page_size.x || y + size.y > page_size.y {
+            return None;
+        }
+
+        self.shelves.push(Shelf {
+            y,
+            height: size.y,
+            used_width: size.x,
+        });
+        Some(UVec2::new(0, y))
+    }
+
+    fn resize(&mut self, size: UVec2) {
+        let old_size = self.image.size;
+        let old_pixels = self.image.image.as_ref();
+        let mut pixels = vec![0; (size.x * size.y * 4) as usize];
+
+        let row_length = (old_size.x * 4) as usize;
+        for y in 0..old_size.y as usize {
+            let old_start = y * row_length;
+            let new_start = y * (size.x * 4) as usize;
+            pixels[new_start..new_start + row_length]
+                .copy_from_slice(&old_pixels[old_start..old_start + row_length]);
+        }
+
+        self.image = FigureImage::new(size, pixels, FigureImageFormat::Rgba);
+    }
+
+    // Copies image to position and extends its edges into the padding around it
+    fn write(&mut self, position: UVec2, image: &FigureImage) {
+        if image.size.x == 0 || image.size.y == 0 {
+            return;
+        }
+
+        let source = image.rgba_bytes();
+        let padding = PADDING as i64;
+        self.image.update_region(
+            position - UVec2::new(PADDING, PADDING),
+            image.size + UVec2::new(PADDING * 2, PADDING * 2),
+            |row, pixels| {
+                let source_y = (row as i64 - padding).clamp(0, image.size.y as i64 - 1);
+                for (x, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+                    let source_x = (x as i64 - padding).clamp(0, image.size.x as i64 - 1);
+                    let source_index = ((source_y * image.size.x as i64 + source_x) * 4) as usize;
+                    pixel.copy_from_slice(&source[source_index..source_index + 4]);
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::UVec2;
+
+    use super::super::figure::{FigureImage, FigureImageFormat};
+    use super::{AtlasRegion, TextureAtlas, PADDING};
+
+    fn image(width: u32, height: u32, value: u8) -> FigureImage {
+        let pixels = vec![value; (width * height * 4) as usize];
+        FigureImage::new(UVec2::new(width, height), pixels, FigureImageFormat::Rgba)
+    }
+
+    fn atlas(initial_size: u32, max_size: u32) -> TextureAtlas {
+        TextureAtlas::new_default().with_page_size(
+            UVec2::new(initial_size, initial_size),
+            UVec2::new(max_size, max_size),
+        )
+    }
+
+    fn page_size(atlas: &TextureAtlas, page: usize) -> UVec2 {
+        atlas.page(page).unwrap().size
+    }
+
+    fn pixel(atlas: &TextureAtlas, page: usize, x: u32, y: u32) -> u8 {
+        let page = atlas.page(page).unwrap();
+        page.image[((y * page.size.x + x) * 4) as usize]
+    }
+
+    // Region with its padding as min and max corner
+    fn padded_bounds(region: &AtlasRegion) -> (UVec2, UVec2) {
+        let padding = PADDING as f32;
+        let min = region.source.position.add_scalar(-padding);
+        let max = region.source.position + region.source.size.add_scalar(padding);
+        (
+            UVec2::new(min.x as u32, min.y as u32),
+            UVec2::new(max.x as u32, max.y as u32),
+        )
+    }
+
+    #[test]
+    fn packed_regions_do_not_overlap() {
+        let mut atlas = atlas(64, 128);
+        let regions = (0..60)
+            .map(|i| {
+                let size = UVec2::new(4 + i * 7 % 23, 3 + i * 11 % 17);
+                atlas.add(&i.to_string(), &image(size.x, size.y, i as u8))
+            })
+            .collect::<Vec<_>>();
+        assert!(atlas.page_count() > 1);
+
+        for (i, a) in regions.iter().enumerate() {
+            let (min_a, max_a) = padded_bounds(a);
+            let size = page_size(&atlas, a.page);
+            assert!(max_a.x <= size.x && max_a.y <= size.y, "{:?}", a);
+
+            for b in regions.iter().skip(i + 1).filter(|b| b.page == a.page) {
+                let (min_b, max_b) = padded_bounds(b);
+                let apart = max_a.x <= min_b.x
+                    || max_b.x <= min_a.x
+                    || max_a.y <= min_b.y
+                    || max_b.y <= min_a.y;
+                assert!(apart, "{:?} overlaps {:?}", a, b);
+            }
+        }
+
+        // Every image still has its own pixels, including the padding copied from its edges
+        for (i, region) in regions.iter().enumerate() {
+            let (min, max) = padded_bounds(region);
+            assert_eq!(pixel(&atlas, region.page, min.x, min.y), i as u8);
+            assert_eq!(pixel(&atlas, region.page, max.x - 1, max.y - 1), i as u8);
+        }
+    }
+
+    #[test]
+    fn oversized_image_gets_its_own_page() {
+        let mut atlas = atlas(64, 128);
+        let small = atlas.add("small", &image(10, 10, 1));
+        let big = atlas.add("big", &image(200, 50, 2));
+        assert_eq!(small.page, 0);
+        assert_eq!(big.page, 1);
+        assert_eq!(page_size(&atlas, 0), UVec2::new(64, 64));
+        assert_eq!(page_size(&atlas, 1), UVec2::new(202, 64));
+
+        // Later small images still go to the first page
+        assert_eq!(atlas.add("next", &image(10, 10, 3)).page, 0);
+        assert_eq!(atlas.page_count(), 2);
+    }
+
+    #[test]
+    fn pages_grow_up_to_the_max_size() {
+        // 20 pixel images take 22 pixels with padding, the initial page only holds one
+        let mut atlas = atlas(32, 64);
+        let first = atlas.add("0", &image(20, 20, 1));
+        assert_eq!(page_size(&atlas, 0), UVec2::new(32, 32));
+
+        let second = atlas.add("1", &image(20, 20, 2));
+        assert_eq!(second.page, 0);
+        assert_eq!(page_size(&atlas, 0), UVec2::new(64, 32));
+        // Growing keeps what was already written
+        assert_eq!(pixel(&atlas, 0, 1, 1), 1);
+
+        atlas.add("2", &image(20, 20, 3));
+        assert_eq!(page_size(&atlas, 0), UVec2::new(64, 64));
+        assert_eq!(atlas.page_count(), 1);
+        assert_eq!(atlas.region("0"), Some(first));
+    }
+
+    #[test]
+    fn full_page_at_max_size_starts_a_new_page() {
+        // Two by two images fit on a page of the max size
+        let mut atlas = atlas(32, 64);
+        for i in 0..4 {
+            assert_eq!(atlas.add(&i.to_string(), &image(20, 20, 1)).page, 0);
+        }
+        let next = atlas.add("4", &image(20, 20, 1));
+        assert_eq!(next.page, 1);
+        assert_eq!(page_size(&atlas, 0), UVec2::new(64, 64));
+        assert_eq!(page_size(&atlas, 1), UVec2::new(32, 32));
+    }
+}
@@ -0,0 +1,301 @@
+use nalgebra_glm::{UVec2, Vec2, Vec4};
+
+use crate::rendering::render_objects::shared::BufferContainer2DUvColor;
+
+use super::figure::{FigureImage, FilterMode};
+
+// Width and height of a chunk in tiles
+pub(crate) const CHUNK_SIZE: u32 = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileRotation {
+    None,
+    // Clockwise
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tile {
+    // Index into the tileset, row by row starting at the top left
+    pub index: u32,
+    // Flipping is applied after rotating
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub rotation: TileRotation,
+}
+
+impl Tile {
+    pub fn new(index: u32) -> Self {
+        Self {
+            index,
+            flip_x: false,
+            flip_y: false,
+            rotation: TileRotation::None,
+        }
+    }
+
+    pub fn with_flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: TileRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct TileLayer {
+    pub visible: bool,
+    pub tint: Vec4,
+    tiles: Vec<Option<Tile>>,
+    pub(crate) chunks: Vec<TileChunk>,
+    // Tint the chunks were built with
+    pub(crate) built_tint: Option<Vec4>,
+}
+
+// Mesh of CHUNK_SIZE by CHUNK_SIZE tiles kept on the gpu, only rebuilt when one of its tiles
+// changed
+#[derive(Clone)]
+pub(crate) struct TileChunk {
+    // Four vertices per tile relative to the map, None when the chunk has no tiles
+    pub(crate) buffers: Option<BufferContainer2DUvColor>,
+    pub(crate) dirty: bool,
+}
+
+// Grid of tiles from a tileset image, drawn in chunks of which only the visible ones are drawn.
+// Draw it with Context::tilemap, which needs mutable access to update the changed chunks.
+#[derive(Clone)]
+pub struct Tilemap {
+    pub tileset: FigureImage,
+    // Size of a tile in the tileset in pixels
+    pub tile_size: UVec2,
+    // Position of the top left corner
+    pub position: Vec2,
+    // Size a tile is drawn with
+    pub cell_size: Vec2,
+    pub filter: FilterMode,
+    size: UVec2,
+    layers: Vec<TileLayer>,
+    // Tileset size, tile size and cell size the chunks were built with
+    pub(crate) built_with: Option<(UVec2, UVec2, Vec2)>,
+}
+
+impl Tilemap {
+    // Creates a map of size tiles with one empty layer
+    pub fn new(tileset: FigureImage, tile_size: UVec2, size: UVec2) -> Self {
+        let mut tilemap = Self {
+            tileset,
+            tile_size,
+            position: Vec2::new(0., 0.),
+            cell_size: Vec2::new(tile_size.x as f32, tile_size.y as f32),
+            filter: FilterMode::Nearest,
+            size,
+            layers: Vec::new(),
+            built_with: None,
+        };
+        tilemap.add_layer();
+        tilemap
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_cell_size(mut self, cell_size: Vec2) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    // Layers are drawn in the order they were added, returns the index of the new layer
+    pub fn add_layer(&mut self) -> usize {
+        let chunk_count = self.chunk_count();
+        self.layers.push(TileLayer {
+            visible: true,
+            tint: Vec4::new(1., 1., 1., 1.),
+            tiles: vec![None; (self.size.x * self.size.y) as usize],
+            chunks: vec![
+                TileChunk {
+                    buffers: None,
+                    dirty: true,
+                };
+                (chunk_count.x * chunk_count.y) as usize
+            ],
+            built_tint: None,
+        });
+        self.layers.len() - 1
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    pub fn layer_mut(&mut self, layer: usize) -> Option<&mut TileLayer> {
+        self.layers.get_mut(layer)
+    }
+
+    pub fn get_tile(&self, layer: usize, x: u32, y: u32) -> Option<Tile> {
+        if x >= self.size.x || y >= self.size.y {
+            return None;
+        }
+        self.layers.get(layer)?.tiles[(y * self.size.x + x) as usize]
+    }
+
+    // Tiles outside of the map or on layers that don't exist are ignored
+    pub fn set_tile(&mut self, layer: usize, x: u32, y: u32, tile: Option<Tile>) {
+        if x >= self.size.x || y >= self.size.y {
+            return;
+        }
+
+        let chunk = self.chunk_index(x / CHUNK_SIZE, y / CHUNK_SIZE);
+        if let Some(layer) = self.layers.get_mut(layer) {
+            layer.tiles[(y * self.size.x + x) as usize] = tile;
+            layer.chunks[chunk].dirty = true;
+        }
+    }
+
+    // Sets the tiles of a layer row by row, -1 means no tile like in Tiled
+    pub fn set_layer_tiles(&mut self, layer: usize, indices: &[i32]) {
+        if self.size.x == 0 {
+            return;
+        }
+
+        for (i, index) in indices.iter().enumerate() {
+            let (x, y) = (i as u32 % self.size.x, i as u32 / self.size.x);
+            let tile = u32::try_from(*index).ok().map(Tile::new);
+            self.set_tile(layer, x, y, tile);
+        }
+    }
+
+    pub(crate) fn chunk_count(&self) -> UVec2 {
+        UVec2::new(
+            (self.size.x + CHUNK_SIZE - 1) / CHUNK_SIZE,
+            (self.size.y + CHUNK_SIZE - 1) / CHUNK_SIZE,
+        )
+    }
+
+    pub(crate) fn chunk_index(&self, x: u32, y: u32) -> usize {
+        (y * self.chunk_count().x + x) as usize
+    }
+
+    pub(crate) fn layers_mut(&mut self) -> &mut [TileLayer] {
+        &mut self.layers
+    }
+}
+
+impl TileLayer {
+    pub(crate) fn tile(&self, map_width: u32, x: u32, y: u32) -> Option<Tile> {
+        self.tiles[(y * map_width + x) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::UVec2;
+
+    use super::{Tile, Tilemap};
+    use crate::public::objects::obj_2d::figure::FigureImage;
+
+    // 40 by 20 tiles, three by two chunks of which the last column and row are partial
+    fn tilemap() -> Tilemap {
+        let tileset = FigureImage::new_with_size(UVec2::new(64, 64));
+        let mut tilemap = Tilemap::new(tileset, UVec2::new(16, 16), UVec2::new(40, 20));
+        mark_clean(&mut tilemap);
+        tilemap
+    }
+
+    fn mark_clean(tilemap: &mut Tilemap) {
+        for layer in tilemap.layers_mut() {
+            for chunk in &mut layer.chunks {
+                chunk.dirty = false;
+            }
+        }
+    }
+
+    fn dirty_chunks(tilemap: &Tilemap, layer: usize) -> Vec<usize> {
+        let chunks = &tilemap.layers()[layer].chunks;
+        (0..chunks.len()).filter(|i| chunks[*i].dirty).collect()
+    }
+
+    #[test]
+    fn new_layers_are_dirty() {
+        let mut tilemap = tilemap();
+        assert_eq!(tilemap.chunk_count(), UVec2::new(3, 2));
+        let layer = tilemap.add_layer();
+        assert_eq!(dirty_chunks(&tilemap, layer), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn set_tile_dirties_only_its_chunk() {
+        let mut tilemap = tilemap();
+        tilemap.set_tile(0, 15, 15, Some(Tile::new(1)));
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![0]);
+
+        mark_clean(&mut tilemap);
+        tilemap.set_tile(0, 16, 0, Some(Tile::new(1)));
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![1]);
+
+        // Partial chunks at the right and bottom edge
+        mark_clean(&mut tilemap);
+        tilemap.set_tile(0, 39, 0, Some(Tile::new(1)));
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![2]);
+
+        mark_clean(&mut tilemap);
+        tilemap.set_tile(0, 39, 19, None);
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![5]);
+
+        mark_clean(&mut tilemap);
+        tilemap.set_tile(0, 0, 19, Some(Tile::new(1)));
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![3]);
+    }
+
+    #[test]
+    fn ignores_tiles_outside_of_the_map() {
+        let mut tilemap = tilemap();
+        tilemap.set_tile(0, 40, 0, Some(Tile::new(1)));
+        tilemap.set_tile(0, 0, 20, Some(Tile::new(1)));
+        tilemap.set_tile(1, 0, 0, Some(Tile::new(1)));
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![]);
+        assert_eq!(tilemap.get_tile(0, 40, 0), None);
+        assert_eq!(tilemap.get_tile(1, 0, 0), None);
+    }
+
+    #[test]
+    fn set_layer_tiles_clears_with_minus_one() {
+        let mut tilemap = tilemap();
+        tilemap.set_tile(0, 0, 0, Some(Tile::new(7)));
+        tilemap.set_tile(0, 2, 0, Some(Tile::new(7)));
+        mark_clean(&mut tilemap);
+
+        tilemap.set_layer_tiles(0, &[-1, 3]);
+        assert_eq!(tilemap.get_tile(0, 0, 0), None);
+        assert_eq!(tilemap.get_tile(0, 1, 0), Some(Tile::new(3)));
+        // Tiles after the given ones are left alone
+        assert_eq!(tilemap.get_tile(0, 2, 0), Some(Tile::new(7)));
+        assert_eq!(dirty_chunks(&tilemap, 0), vec![0]);
+
+        // Rows wrap at the width of the map
+        let mut indices = vec![-1; 41];
+        indices[40] = 2;
+        tilemap.set_layer_tiles(0, &indices);
+        assert_eq!(tilemap.get_tile(0, 0, 1), Some(Tile::new(2)));
+    }
+}
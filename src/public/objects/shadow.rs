@@ -0,0 +1,79 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+// Soft shadow behind a shape, like box-shadow in CSS. It has the outline of the shape including
+// its border, grown by spread and blurred over blur pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shadow {
+    pub offset: Vec2,
+    pub blur: f32,
+    pub spread: f32,
+    pub color: Vec4,
+}
+
+impl Shadow {
+    pub fn new(color: Vec4, blur: f32) -> Self {
+        Self {
+            offset: Vec2::new(0., 0.),
+            blur,
+            spread: 0.,
+            color,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_blur(mut self, blur: f32) -> Self {
+        self.blur = blur;
+        self
+    }
+
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+// Shadow centred on the shape, drawn behind it next to a drop shadow
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glow {
+    pub blur: f32,
+    pub spread: f32,
+    pub color: Vec4,
+}
+
+impl Glow {
+    pub fn new(color: Vec4, blur: f32) -> Self {
+        Self {
+            blur,
+            spread: 0.,
+            color,
+        }
+    }
+
+    pub fn with_blur(mut self, blur: f32) -> Self {
+        self.blur = blur;
+        self
+    }
+
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub(crate) fn shadow(&self) -> Shadow {
+        Shadow::new(self.color, self.blur).with_spread(self.spread)
+    }
+}
@@ -0,0 +1,96 @@
+use std::ops::Mul;
+
+use nalgebra_glm::{self as glm, Mat3, Vec2, Vec3};
+
+// Affine 2D transform. Like everything else in 2D, positive rotations are clockwise on screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2D {
+    pub matrix: Mat3,
+}
+
+impl Transform2D {
+    pub fn identity() -> Self {
+        Self {
+            matrix: Mat3::identity(),
+        }
+    }
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self {
+            matrix: glm::translation2d(&translation),
+        }
+    }
+
+    pub fn from_rotation(angle: f32) -> Self {
+        Self {
+            matrix: glm::rotation2d(angle),
+        }
+    }
+
+    pub fn from_scale(scale: Vec2) -> Self {
+        Self {
+            matrix: glm::scaling2d(&scale),
+        }
+    }
+
+    // translate, rotate and scale apply before the existing transform,
+    // so they work in the local coordinate system like in Processing
+    pub fn translate(self, translation: Vec2) -> Self {
+        self * Self::from_translation(translation)
+    }
+
+    pub fn rotate(self, angle: f32) -> Self {
+        self * Self::from_rotation(angle)
+    }
+
+    pub fn scale(self, scale: Vec2) -> Self {
+        self * Self::from_scale(scale)
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        self.matrix.try_inverse().map(|matrix| Self { matrix })
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        (self.matrix * Vec3::new(point.x, point.y, 1.)).xy()
+    }
+
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        (self.matrix * Vec3::new(vector.x, vector.y, 0.)).xy()
+    }
+
+    // Column major 2x2 part, as used by the mat2 in the 2D shaders
+    pub(crate) fn linear(&self) -> [[f32; 2]; 2] {
+        [
+            [self.matrix[(0, 0)], self.matrix[(1, 0)]],
+            [self.matrix[(0, 1)], self.matrix[(1, 1)]],
+        ]
+    }
+
+    pub(crate) fn translation(&self) -> [f32; 2] {
+        [self.matrix[(0, 2)], self.matrix[(1, 2)]]
+    }
+
+    // Largest factor a length can be scaled by, used to pick tessellation tolerances
+    pub(crate) fn max_scale(&self) -> f32 {
+        let x = self.transform_vector(Vec2::new(1., 0.)).norm();
+        let y = self.transform_vector(Vec2::new(0., 1.)).norm();
+        x.max(y)
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Transform2D {
+    type Output = Transform2D;
+
+    fn mul(self, rhs: Transform2D) -> Transform2D {
+        Transform2D {
+            matrix: self.matrix * rhs.matrix,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+// Splits a polyline into the pieces that are "on" in the dash pattern. The pattern alternates
+// between dash and gap lengths and is measured along the length of the polyline, so dashes
+// continue around corners. Every point comes with a color, points where a dash starts or ends get
+// the color interpolated between the points around them.
+pub(crate) fn dash_polyline(
+    points: &[(Vec2, Vec4)],
+    closed: bool,
+    pattern: &[f32],
+    offset: f32,
+) -> Vec<Vec<(Vec2, Vec4)>> {
+    // Invalid patterns draw the polyline without dashes
+    if pattern.iter().any(|length| *length < 0.) || pattern.iter().sum::<f32>() <= 0. {
+        let mut line = points.to_vec();
+        if closed && points.len() > 2 {
+            line.push(points[0]);
+        }
+        return vec![line];
+    }
+
+    // An odd pattern is repeated once so dashes and gaps keep alternating
+    let pattern = if pattern.len() % 2 == 1 {
+        [pattern, pattern].concat()
+    } else {
+        pattern.to_vec()
+    };
+    let pattern_length: f32 = pattern.iter().sum();
+
+    let mut index = 0;
+    let mut remaining = pattern[0];
+    let mut offset = offset.rem_euclid(pattern_length);
+    while offset > 0. {
+        if offset < remaining {
+            remaining -= offset;
+            break;
+        }
+        offset -= remaining;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+
+    let mut segments = points
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect::<Vec<_>>();
+    if closed && points.len() > 2 {
+        segments.push((points[points.len() - 1], points[0]));
+    }
+
+    let mut dashes = Vec::new();
+    let mut current = Vec::new();
+    let mut on = index % 2 == 0;
+    let starts_on = on;
+    if let (true, Some(first)) = (on, points.first()) {
+        current.push(first.clone());
+    }
+
+    for (start, end) in segments {
+        let ((start, start_color), (end, end_color)) = (start, end);
+        let length = (end - start).norm();
+        if length == 0. {
+            continue;
+        }
+        let dir = (end - start) / length;
+        let mut position = 0.;
+
+        while length - position > remaining {
+            position += remaining;
+            let t = position / length;
+            current.push((start + dir * position, start_color.lerp(&end_color, t)));
+            if on {
+                dashes.push(std::mem::take(&mut current));
+            }
+            on = !on;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+
+        remaining -= length - position;
+        if on {
+            current.push((end, end_color));
+        }
+    }
+
+    let ends_on = on && current.len() >= 2;
+    if ends_on {
+        dashes.push(current);
+    }
+
+    // A dash running over the start of a closed polyline is joined with the first dash, so it
+    // gets a join rather than two ends
+    if closed && points.len() > 2 && starts_on && ends_on && dashes.len() > 1 {
+        let first = dashes.remove(0);
+        if let Some(last) = dashes.last_mut() {
+            last.extend(first.into_iter().skip(1));
+        }
+    }
+    dashes
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{Vec2, Vec4};
+
+    use super::dash_polyline;
+
+    const WHITE: Vec4 = Vec4::new(1., 1., 1., 1.);
+
+    // Dashes a white polyline and leaves out the colors
+    fn dash(points: &[(f32, f32)], closed: bool, pattern: &[f32], offset: f32) -> Vec<Vec<Vec2>> {
+        let points = points
+            .iter()
+            .map(|&(x, y)| (Vec2::new(x, y), WHITE))
+            .collect::<Vec<_>>();
+        dash_polyline(&points, closed, pattern, offset)
+            .into_iter()
+            .map(|dash| dash.into_iter().map(|(point, _)| point).collect())
+            .collect()
+    }
+
+    fn points(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    const SQUARE: [(f32, f32); 4] = [(0., 0.), (10., 0.), (10., 10.), (0., 10.)];
+
+    #[test]
+    fn continues_around_corners() {
+        let dashes = dash(&[(0., 0.), (10., 0.), (10., 10.)], false, &[3., 1.], 0.);
+        assert_eq!(dashes.len(), 5);
+        assert_eq!(dashes[0], points(&[(0., 0.), (3., 0.)]));
+        assert_eq!(dashes[2], points(&[(8., 0.), (10., 0.), (10., 1.)]));
+        assert_eq!(dashes[4], points(&[(10., 6.), (10., 9.)]));
+    }
+
+    #[test]
+    fn repeats_odd_patterns() {
+        // Played as 3, 1, 2, 3, 1, 2 so the 3 is a gap the second time
+        let dashes = dash(&[(0., 0.), (12., 0.)], false, &[3., 1., 2.], 0.);
+        assert_eq!(
+            dashes,
+            vec![
+                points(&[(0., 0.), (3., 0.)]),
+                points(&[(4., 0.), (6., 0.)]),
+                points(&[(9., 0.), (10., 0.)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn wraps_negative_and_large_offsets() {
+        let expected = vec![
+            points(&[(1., 0.), (4., 0.)]),
+            points(&[(5., 0.), (8., 0.)]),
+            points(&[(9., 0.), (10., 0.)]),
+        ];
+        for offset in [3., -1., 403.] {
+            assert_eq!(
+                dash(&[(0., 0.), (10., 0.)], false, &[3., 1.], offset),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn zero_length_dashes_are_dots() {
+        let dashes = dash(&[(0., 0.), (10., 0.)], false, &[0., 5.], 0.);
+        assert_eq!(
+            dashes,
+            vec![points(&[(0., 0.), (0., 0.)]), points(&[(5., 0.), (5., 0.)])]
+        );
+    }
+
+    #[test]
+    fn joins_dash_over_the_start_of_closed_polylines() {
+        let dashes = dash(&SQUARE, true, &[5., 5.], 0.);
+        assert_eq!(dashes.len(), 4);
+        assert_eq!(dashes[0], points(&[(0., 0.), (5., 0.)]));
+
+        // On from 38 to 3, the piece before the start is joined with the one after it
+        let dashes = dash(&SQUARE, true, &[5., 5.], 2.);
+        assert_eq!(dashes.len(), 4);
+        assert_eq!(dashes[0], points(&[(8., 0.), (10., 0.), (10., 3.)]));
+        assert_eq!(dashes[3], points(&[(0., 2.), (0., 0.), (3., 0.)]));
+    }
+
+    #[test]
+    fn invalid_patterns_draw_the_whole_polyline() {
+        for pattern in [&[][..], &[0., 0.], &[4., -1.]] {
+            let mut closed = points(&SQUARE);
+            closed.push(Vec2::new(0., 0.));
+            assert_eq!(dash(&SQUARE, true, pattern, 0.), vec![closed]);
+            assert_eq!(dash(&SQUARE, false, pattern, 0.), vec![points(&SQUARE)]);
+        }
+    }
+
+    #[test]
+    fn interpolates_colors() {
+        let red = Vec4::new(1., 0., 0., 1.);
+        let blue = Vec4::new(0., 0., 1., 1.);
+        let points = [(Vec2::new(0., 0.), red), (Vec2::new(10., 0.), blue)];
+        let dashes = dash_polyline(&points, false, &[3., 2.], 0.);
+        assert_eq!(dashes[0][0], (Vec2::new(0., 0.), red));
+        assert_eq!(dashes[0][1], (Vec2::new(3., 0.), red.lerp(&blue, 0.3)));
+        assert_eq!(dashes[1][0], (Vec2::new(5., 0.), red.lerp(&blue, 0.5)));
+    }
+}
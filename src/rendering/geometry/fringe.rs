@@ -0,0 +1,87 @@
+use nalgebra_glm::Vec2;
+
+use super::triangulation::signed_area;
+
+// Width in pixels of the fringe around fills
+pub(crate) const FRINGE_WIDTH: f32 = 1.;
+// Outer fringe vertices at sharp corners are kept within this times the width
+const MITER_LIMIT: f32 = 4.;
+const MIN_SEGMENT_LENGTH: f32 = 0.0001;
+
+// Strip along the outside of the contours of a fill, used to smooth its edges. Every vertex has
+// its distance outside of the fill, so coverage can be computed from it per pixel.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Fringe {
+    pub(crate) positions: Vec<Vec2>,
+    pub(crate) distances: Vec<f32>,
+    pub(crate) indices: Vec<u32>,
+}
+
+// Holes have to be wound opposite to the first contour, like the contours of polygons
+pub(crate) fn fringe(contours: &[Vec<Vec2>], width: f32) -> Fringe {
+    let mut fringe = Fringe::default();
+    let fill_side = match contours.first() {
+        Some(contour) => signed_area(contour).signum(),
+        None => return fringe,
+    };
+    if fill_side == 0. || width <= 0. {
+        return fringe;
+    }
+
+    for contour in contours {
+        let mut points: Vec<Vec2> = Vec::with_capacity(contour.len());
+        for point in contour {
+            if let Some(last) = points.last() {
+                if (point - last).norm() < MIN_SEGMENT_LENGTH {
+                    continue;
+                }
+            }
+            points.push(*point);
+        }
+        if points.len() > 2 && (points[0] - points[points.len() - 1]).norm() < MIN_SEGMENT_LENGTH {
+            points.pop();
+        }
+        if points.len() < 3 {
+            continue;
+        }
+
+        // Normals point away from the filled side of every edge
+        let count = points.len();
+        let normals = (0..count)
+            .map(|i| {
+                let dir = (points[(i + 1) % count] - points[i]).normalize();
+                Vec2::new(dir.y, -dir.x) * fill_side
+            })
+            .collect::<Vec<_>>();
+
+        let first_index = fringe.positions.len() as u32;
+        for i in 0..count {
+            let normal_in = normals[(i + count - 1) % count];
+            let normal_out = normals[i];
+            let miter = normal_in + normal_out;
+            let offset = if miter.norm() > 1e-4 {
+                let miter = miter.normalize();
+                miter * width / miter.dot(&normal_in).max(1. / MITER_LIMIT)
+            } else {
+                normal_out * width
+            };
+
+            fringe.positions.push(points[i]);
+            fringe.distances.push(0.);
+            fringe.positions.push(points[i] + offset);
+            fringe.distances.push(width);
+        }
+
+        for i in 0..count as u32 {
+            let inner = first_index + i * 2;
+            let next_inner = first_index + (i + 1) % count as u32 * 2;
+            fringe
+                .indices
+                .extend_from_slice(&[inner, inner + 1, next_inner]);
+            fringe
+                .indices
+                .extend_from_slice(&[next_inner, inner + 1, next_inner + 1]);
+        }
+    }
+    fringe
+}
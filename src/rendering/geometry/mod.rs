@@ -0,0 +1,97 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+pub(crate) mod dash;
+pub(crate) mod fringe;
+pub(crate) mod path;
+pub(crate) mod stroke;
+pub(crate) mod triangulation;
+
+// Maximum distance in pixels between a round curve and the segments approximating it
+pub(crate) const ROUND_TOLERANCE: f32 = 0.25;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Mesh2D {
+    pub(crate) positions: Vec<Vec2>,
+    pub(crate) colors: Vec<Vec4>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl Mesh2D {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push_vertex(&mut self, position: Vec2, color: Vec4) -> u32 {
+        self.positions.push(position);
+        self.colors.push(color);
+        (self.positions.len() - 1) as u32
+    }
+
+    pub(crate) fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    // Adds a fan around center, starting at start_angle and rotating over sweep radians
+    pub(crate) fn push_fan(
+        &mut self,
+        center: Vec2,
+        color: Vec4,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    ) {
+        let segments = arc_segments(radius, sweep, ROUND_TOLERANCE);
+        let center_index = self.push_vertex(center, color);
+        let mut prev_index = self.push_vertex(center + angle_to_vec(start_angle) * radius, color);
+        for i in 1..=segments {
+            let angle = start_angle + sweep * i as f32 / segments as f32;
+            let index = self.push_vertex(center + angle_to_vec(angle) * radius, color);
+            self.push_triangle(center_index, prev_index, index);
+            prev_index = index;
+        }
+    }
+
+    pub(crate) fn append(&mut self, other: Mesh2D) {
+        let index_offset = self.positions.len() as u32;
+        self.positions.extend(other.positions);
+        self.colors.extend(other.colors);
+        self.indices
+            .extend(other.indices.iter().map(|index| index + index_offset));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+pub(crate) fn angle_to_vec(angle: f32) -> Vec2 {
+    Vec2::new(angle.cos(), angle.sin())
+}
+
+// Amount of segments needed to approximate an arc within tolerance
+pub(crate) fn arc_segments(radius: f32, sweep: f32, tolerance: f32) -> u32 {
+    if radius <= tolerance {
+        return 1;
+    }
+    let step = 2. * (1. - tolerance / radius).acos();
+    (sweep.abs() / step).ceil().max(1.) as u32
+}
+
+pub(crate) fn cross_2d(a: &Vec2, b: &Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+// Returns the top left corner and the size of the smallest box containing all points
+pub(crate) fn bounding_box(points: &[Vec2]) -> (Vec2, Vec2) {
+    if points.is_empty() {
+        return (Vec2::zeros(), Vec2::zeros());
+    }
+
+    let mut min = points[0];
+    let mut max = points[0];
+    for point in points {
+        min = min.inf(point);
+        max = max.sup(point);
+    }
+    (min, max - min)
+}
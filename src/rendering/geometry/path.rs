@@ -0,0 +1,301 @@
+use std::f32::consts::PI;
+
+use nalgebra_glm::Vec2;
+
+use crate::public::objects::obj_2d::path::PathCommand;
+
+use super::{angle_to_vec, arc_segments, cross_2d};
+
+#[derive(Clone, Debug)]
+pub(crate) struct SubPath {
+    pub(crate) points: Vec<Vec2>,
+    pub(crate) closed: bool,
+}
+
+// Turns the path commands into polylines, curves are split into segments that stay within
+// tolerance of the real curve
+pub(crate) fn flatten(commands: &[PathCommand], tolerance: f32) -> Vec<SubPath> {
+    let mut sub_paths = Vec::new();
+    let mut points: Vec<Vec2> = Vec::new();
+
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(point) => {
+                finish_sub_path(&mut sub_paths, &mut points, false);
+                points.push(point);
+            }
+            PathCommand::LineTo(point) => points.push(point),
+            PathCommand::QuadTo(control, point) => match points.last().cloned() {
+                Some(start) => flatten_quad(&mut points, start, control, point, tolerance),
+                None => points.push(point),
+            },
+            PathCommand::CubicTo(control_0, control_1, point) => match points.last().cloned() {
+                Some(start) => {
+                    flatten_cubic(&mut points, start, control_0, control_1, point, tolerance)
+                }
+                None => points.push(point),
+            },
+            PathCommand::ArcTo(p0, p1, radius) => match points.last().cloned() {
+                Some(start) => flatten_arc_to(&mut points, start, p0, p1, radius, tolerance),
+                None => points.push(p0),
+            },
+            PathCommand::Close => {
+                let start = points.first().cloned();
+                finish_sub_path(&mut sub_paths, &mut points, true);
+                // Like the HTML canvas, drawing continues from the start of the closed path
+                if let Some(start) = start {
+                    points.push(start);
+                }
+            }
+        }
+    }
+    finish_sub_path(&mut sub_paths, &mut points, false);
+
+    sub_paths
+}
+
+fn finish_sub_path(sub_paths: &mut Vec<SubPath>, points: &mut Vec<Vec2>, closed: bool) {
+    let points = std::mem::take(points);
+    if points.len() >= 2 {
+        sub_paths.push(SubPath { points, closed });
+    }
+}
+
+// Segment count from Wang's formula, based on the largest second difference of the points
+fn curve_segments(second_difference: f32, factor: f32, tolerance: f32) -> u32 {
+    ((factor * second_difference / tolerance).sqrt().ceil() as u32).clamp(1, 1000)
+}
+
+fn flatten_quad(points: &mut Vec<Vec2>, p0: Vec2, p1: Vec2, p2: Vec2, tolerance: f32) {
+    let segments = curve_segments((p0 - 2. * p1 + p2).norm(), 0.25, tolerance);
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1. - t;
+        points.push(mt * mt * p0 + 2. * mt * t * p1 + t * t * p2);
+    }
+}
+
+fn flatten_cubic(points: &mut Vec<Vec2>, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, tolerance: f32) {
+    let second_difference = (p0 - 2. * p1 + p2).norm().max((p1 - 2. * p2 + p3).norm());
+    let segments = curve_segments(second_difference, 0.75, tolerance);
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1. - t;
+        points.push(
+            mt * mt * mt * p0 + 3. * mt * mt * t * p1 + 3. * mt * t * t * p2 + t * t * t * p3,
+        );
+    }
+}
+
+fn flatten_arc_to(
+    points: &mut Vec<Vec2>,
+    start: Vec2,
+    corner: Vec2,
+    end: Vec2,
+    radius: f32,
+    tolerance: f32,
+) {
+    let to_start = start - corner;
+    let to_end = end - corner;
+    if radius <= 0.
+        || to_start.norm() == 0.
+        || to_end.norm() == 0.
+        || cross_2d(&to_start, &to_end).abs() < 1e-6
+    {
+        points.push(corner);
+        return;
+    }
+
+    let dir_start = to_start.normalize();
+    let dir_end = to_end.normalize();
+    let half_angle = dir_start.dot(&dir_end).clamp(-1., 1.).acos() * 0.5;
+
+    let tangent_distance = radius / half_angle.tan();
+    let tangent_start = corner + dir_start * tangent_distance;
+    let tangent_end = corner + dir_end * tangent_distance;
+    let center = corner + (dir_start + dir_end).normalize() * (radius / half_angle.sin());
+
+    let start_angle = (tangent_start - center).y.atan2((tangent_start - center).x);
+    let end_angle = (tangent_end - center).y.atan2((tangent_end - center).x);
+    let mut sweep = end_angle - start_angle;
+    if sweep > PI {
+        sweep -= 2. * PI;
+    } else if sweep < -PI {
+        sweep += 2. * PI;
+    }
+
+    points.push(tangent_start);
+    let segments = arc_segments(radius, sweep, tolerance);
+    for i in 1..=segments {
+        let angle = start_angle + sweep * i as f32 / segments as f32;
+        points.push(center + angle_to_vec(angle) * radius);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::Vec2;
+
+    use super::{curve_segments, flatten};
+    use crate::public::objects::obj_2d::path::PathCommand::{self, *};
+
+    fn v(x: f32, y: f32) -> Vec2 {
+        Vec2::new(x, y)
+    }
+
+    fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+        let ab = b - a;
+        let t = ((point - a).dot(&ab) / ab.norm_squared()).clamp(0., 1.);
+        (point - (a + ab * t)).norm()
+    }
+
+    fn distance_to_polyline(point: Vec2, points: &[Vec2]) -> f32 {
+        points
+            .windows(2)
+            .map(|pair| distance_to_segment(point, pair[0], pair[1]))
+            .fold(f32::MAX, f32::min)
+    }
+
+    #[test]
+    fn close_continues_from_the_start() {
+        let sub_paths = flatten(
+            &[
+                MoveTo(v(0., 0.)),
+                LineTo(v(10., 0.)),
+                LineTo(v(10., 10.)),
+                Close,
+                LineTo(v(0., 10.)),
+            ],
+            0.25,
+        );
+        assert_eq!(sub_paths.len(), 2);
+        assert!(sub_paths[0].closed);
+        assert_eq!(
+            sub_paths[0].points,
+            vec![v(0., 0.), v(10., 0.), v(10., 10.)]
+        );
+        assert!(!sub_paths[1].closed);
+        assert_eq!(sub_paths[1].points, vec![v(0., 0.), v(0., 10.)]);
+
+        // Without anything after it the point left by Close is not a sub path of its own
+        let sub_paths = flatten(
+            &[
+                MoveTo(v(0., 0.)),
+                LineTo(v(10., 0.)),
+                LineTo(v(10., 10.)),
+                Close,
+            ],
+            0.25,
+        );
+        assert_eq!(sub_paths.len(), 1);
+    }
+
+    #[test]
+    fn arc_to_without_current_point_moves_to_the_corner() {
+        let sub_paths = flatten(
+            &[ArcTo(v(10., 0.), v(10., 10.), 2.), LineTo(v(0., 10.))],
+            0.25,
+        );
+        assert_eq!(sub_paths[0].points, vec![v(10., 0.), v(0., 10.)]);
+    }
+
+    #[test]
+    fn degenerate_arc_to_is_a_line_to_the_corner() {
+        let commands = [
+            // Collinear, forwards and back
+            ArcTo(v(10., 0.), v(20., 0.), 5.),
+            ArcTo(v(20., 0.), v(15., 0.), 5.),
+            // No radius
+            ArcTo(v(20., 10.), v(0., 10.), 0.),
+            // Corner on the current point
+            ArcTo(v(20., 10.), v(0., 10.), 5.),
+        ];
+        let mut path = vec![MoveTo(v(0., 0.))];
+        path.extend(commands);
+        let sub_paths = flatten(&path, 0.25);
+        assert_eq!(
+            sub_paths[0].points,
+            vec![v(0., 0.), v(10., 0.), v(20., 0.), v(20., 10.), v(20., 10.)]
+        );
+    }
+
+    #[test]
+    fn arc_to_touches_both_lines() {
+        // Right angle, the arc is a quarter circle around (8, 2)
+        let sub_paths = flatten(
+            &[MoveTo(v(0., 0.)), ArcTo(v(10., 0.), v(10., 10.), 2.)],
+            0.01,
+        );
+        let points = &sub_paths[0].points;
+        assert_eq!(points[1], v(8., 0.));
+        assert!((points[points.len() - 1] - v(10., 2.)).norm() < 1e-4);
+        for point in &points[1..] {
+            assert!(
+                ((point - v(8., 2.)).norm() - 2.).abs() < 1e-4,
+                "{:?}",
+                point
+            );
+        }
+
+        // Turning by 45 degrees the tangent points are radius * tan(22.5) from the corner
+        let sub_paths = flatten(
+            &[MoveTo(v(0., 0.)), ArcTo(v(10., 0.), v(20., 10.), 4.)],
+            0.01,
+        );
+        let points = &sub_paths[0].points;
+        let distance = 4. * (std::f32::consts::PI / 8.).tan();
+        let diagonal = v(1., 1.).normalize();
+        assert!((points[1] - v(10. - distance, 0.)).norm() < 1e-4);
+        assert!((points[points.len() - 1] - (v(10., 0.) + diagonal * distance)).norm() < 1e-4);
+        let center = v(10. - distance, 4.);
+        for point in &points[1..] {
+            assert!(((point - center).norm() - 4.).abs() < 1e-3, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn curves_stay_within_tolerance() {
+        let curves: [PathCommand; 2] = [
+            QuadTo(v(50., 100.), v(100., 0.)),
+            CubicTo(v(0., 100.), v(100., -100.), v(100., 0.)),
+        ];
+        let exact = |curve: &PathCommand, t: f32| {
+            let p0 = v(0., 0.);
+            let mt = 1. - t;
+            match *curve {
+                QuadTo(p1, p2) => mt * mt * p0 + 2. * mt * t * p1 + t * t * p2,
+                CubicTo(p1, p2, p3) => {
+                    mt * mt * mt * p0
+                        + 3. * mt * mt * t * p1
+                        + 3. * mt * t * t * p2
+                        + t * t * t * p3
+                }
+                _ => unreachable!(),
+            }
+        };
+
+        for curve in &curves {
+            let mut last_count = 0;
+            for tolerance in [4., 1., 0.25, 0.05] {
+                let sub_paths = flatten(&[MoveTo(v(0., 0.)), *curve], tolerance);
+                let points = &sub_paths[0].points;
+                assert!(points.len() > last_count);
+                last_count = points.len();
+
+                for i in 0..=200 {
+                    let point = exact(curve, i as f32 / 200.);
+                    let distance = distance_to_polyline(point, points);
+                    assert!(distance <= tolerance * 1.01, "{} > {}", distance, tolerance);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn curve_segments_are_bounded() {
+        assert_eq!(curve_segments(0., 0.25, 0.25), 1);
+        assert_eq!(curve_segments(1e9, 0.75, 0.01), 1000);
+        assert_eq!(curve_segments(16., 0.25, 1.), 2);
+        assert_eq!(curve_segments(16., 0.25, 0.25), 4);
+    }
+}
@@ -0,0 +1,431 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::public::objects::{
+    obj_2d::{
+        line::{Line, LineCap, LineJoin},
+        path::PathStroke,
+        polygon::Polygon,
+        polyline::Polyline,
+    },
+    Border, StrokeStyle,
+};
+
+use super::{
+    angle_to_vec, arc_segments, cross_2d, dash::dash_polyline, path::SubPath,
+    triangulation::signed_area, Mesh2D, ROUND_TOLERANCE,
+};
+
+// Miters longer than this times the half thickness fall back to a bevel
+const MITER_LIMIT: f32 = 4.;
+const MIN_SEGMENT_LENGTH: f32 = 0.0001;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Stroke {
+    pub(crate) points: Vec<Vec2>,
+    pub(crate) colors: Vec<Vec4>,
+    pub(crate) thickness: f32,
+    pub(crate) join: LineJoin,
+    pub(crate) cap: LineCap,
+    pub(crate) closed: bool,
+    // Moves the stroke sideways along the normal, which points to the right of the direction
+    // of travel on screen
+    pub(crate) offset: f32,
+    pub(crate) style: StrokeStyle,
+}
+
+impl From<Line> for Stroke {
+    fn from(line: Line) -> Self {
+        Self {
+            points: vec![line.p0, line.p1],
+            colors: vec![line.color, line.color],
+            thickness: line.thickness as f32,
+            join: LineJoin::Miter,
+            cap: line.cap,
+            closed: false,
+            offset: 0.,
+            style: line.style,
+        }
+    }
+}
+
+impl From<Polyline> for Stroke {
+    fn from(polyline: Polyline) -> Self {
+        let colors = (0..polyline.points.len())
+            .map(|i| match &polyline.vertex_colors {
+                Some(colors) => colors.get(i).cloned().unwrap_or(polyline.color),
+                None => polyline.color,
+            })
+            .collect();
+
+        Self {
+            points: polyline.points,
+            colors,
+            thickness: polyline.thickness,
+            join: polyline.join,
+            cap: polyline.cap,
+            closed: polyline.closed,
+            offset: 0.,
+            style: StrokeStyle::new_solid(),
+        }
+    }
+}
+
+impl Stroke {
+    pub(crate) fn tessellate(&self) -> Mesh2D {
+        if !self.style.is_dashed() {
+            return self.tessellate_solid();
+        }
+
+        // Every dash is stroked on its own, in the colors along the stroke where it lies
+        let points = self
+            .points
+            .iter()
+            .copied()
+            .zip(self.colors.iter().copied())
+            .collect::<Vec<_>>();
+        let cap = if self.style.round_caps {
+            LineCap::Round
+        } else {
+            self.cap
+        };
+
+        let mut mesh = Mesh2D::new();
+        let dashes = dash_polyline(
+            &points,
+            self.closed,
+            &self.style.dashes,
+            self.style.dash_offset,
+        );
+        for dash in dashes {
+            let (points, colors) = dash.into_iter().unzip();
+            let stroke = Stroke {
+                colors,
+                points,
+                thickness: self.thickness,
+                join: self.join,
+                cap,
+                closed: false,
+                offset: self.offset,
+                style: StrokeStyle::new_solid(),
+            };
+            mesh.append(stroke.tessellate_solid());
+        }
+        mesh
+    }
+
+    fn tessellate_solid(&self) -> Mesh2D {
+        let mut mesh = Mesh2D::new();
+        let half_width = self.thickness * 0.5;
+        if half_width <= 0. {
+            return mesh;
+        }
+
+        let mut points: Vec<(Vec2, Vec4)> = Vec::with_capacity(self.points.len());
+        for (point, color) in self.points.iter().zip(self.colors.iter()) {
+            if let Some((last, _)) = points.last() {
+                if (point - last).norm() < MIN_SEGMENT_LENGTH {
+                    continue;
+                }
+            }
+            points.push((point.clone(), color.clone()));
+        }
+
+        if self.closed && points.len() > 2 {
+            let (first, _) = points[0];
+            let (last, _) = points[points.len() - 1];
+            if (first - last).norm() < MIN_SEGMENT_LENGTH {
+                points.pop();
+            }
+        }
+        let closed = self.closed && points.len() > 2;
+
+        if points.len() == 1 {
+            let (point, color) = points[0];
+            self.add_dot(&mut mesh, point, color, half_width);
+            return mesh;
+        }
+        if points.len() < 2 {
+            return mesh;
+        }
+
+        let count = points.len();
+        let segment_count = if closed { count } else { count - 1 };
+        for i in 0..segment_count {
+            let (mut p0, c0) = points[i];
+            let (mut p1, c1) = points[(i + 1) % count];
+            let dir = (p1 - p0).normalize();
+            let normal = Vec2::new(-dir.y, dir.x);
+            let (left, right) = (self.offset + half_width, self.offset - half_width);
+
+            if !closed && self.cap == LineCap::Square {
+                if i == 0 {
+                    p0 -= dir * half_width;
+                }
+                if i == segment_count - 1 {
+                    p1 += dir * half_width;
+                }
+            }
+
+            let a = mesh.push_vertex(p0 + normal * left, c0);
+            let b = mesh.push_vertex(p0 + normal * right, c0);
+            let c = mesh.push_vertex(p1 + normal * left, c1);
+            let d = mesh.push_vertex(p1 + normal * right, c1);
+            mesh.push_triangle(a, b, c);
+            mesh.push_triangle(c, b, d);
+        }
+
+        let joints = if closed { 0..count } else { 1..count - 1 };
+        for i in joints {
+            let (point, color) = points[i];
+            let (prev, _) = points[(i + count - 1) % count];
+            let (next, _) = points[(i + 1) % count];
+            self.add_join(
+                &mut mesh,
+                point,
+                color,
+                (point - prev).normalize(),
+                (next - point).normalize(),
+                half_width,
+            );
+        }
+
+        if !closed && self.cap == LineCap::Round {
+            let (first, first_color) = points[0];
+            let (second, _) = points[1];
+            add_round_cap(
+                &mut mesh,
+                first,
+                first_color,
+                (first - second).normalize(),
+                half_width,
+            );
+
+            let (last, last_color) = points[count - 1];
+            let (before_last, _) = points[count - 2];
+            add_round_cap(
+                &mut mesh,
+                last,
+                last_color,
+                (last - before_last).normalize(),
+                half_width,
+            );
+        }
+
+        mesh
+    }
+
+    fn add_join(
+        &self,
+        mesh: &mut Mesh2D,
+        point: Vec2,
+        color: Vec4,
+        dir_in: Vec2,
+        dir_out: Vec2,
+        half_width: f32,
+    ) {
+        let cross = cross_2d(&dir_in, &dir_out);
+        if cross.abs() < 1e-6 && dir_in.dot(&dir_out) > 0. {
+            return;
+        }
+
+        // The join is only needed on the outer side of the turn
+        let side = if cross > 0. { -1. } else { 1. };
+        let normal_in = Vec2::new(-dir_in.y, dir_in.x) * side;
+        let normal_out = Vec2::new(-dir_out.y, dir_out.x) * side;
+
+        // How far the stroke reaches out on the outer side of the turn
+        let reach = half_width + self.offset * side;
+        if reach <= 0. {
+            return;
+        }
+
+        match self.join {
+            LineJoin::Round => {
+                let start_angle = normal_in.y.atan2(normal_in.x);
+                let mut sweep = cross_2d(&normal_in, &normal_out).atan2(normal_in.dot(&normal_out));
+                // A full turn back has two possible sweeps, take the one pointing forward
+                if (sweep.abs() - PI).abs() < 1e-4 {
+                    let mid = start_angle + sweep * 0.5;
+                    if Vec2::new(mid.cos(), mid.sin()).dot(&dir_in) < 0. {
+                        sweep = -sweep;
+                    }
+                }
+                mesh.push_fan(point, color, reach, start_angle, sweep);
+            }
+            LineJoin::Miter => {
+                let miter = normal_in + normal_out;
+                if miter.norm() > 1e-4 {
+                    let miter = miter.normalize();
+                    let miter_length = reach / miter.dot(&normal_in);
+                    if miter_length <= MITER_LIMIT * reach {
+                        let center = mesh.push_vertex(point, color);
+                        let a = mesh.push_vertex(point + normal_in * reach, color);
+                        let tip = mesh.push_vertex(point + miter * miter_length, color);
+                        let b = mesh.push_vertex(point + normal_out * reach, color);
+                        mesh.push_triangle(center, a, tip);
+                        mesh.push_triangle(center, tip, b);
+                        return;
+                    }
+                }
+                add_bevel(mesh, point, color, normal_in, normal_out, reach);
+            }
+            LineJoin::Bevel => add_bevel(mesh, point, color, normal_in, normal_out, reach),
+        }
+    }
+
+    fn add_dot(&self, mesh: &mut Mesh2D, point: Vec2, color: Vec4, half_width: f32) {
+        match self.cap {
+            LineCap::Round => mesh.push_fan(point, color, half_width, 0., 2. * PI),
+            LineCap::Square => {
+                let a = mesh.push_vertex(point + Vec2::new(-half_width, -half_width), color);
+                let b = mesh.push_vertex(point + Vec2::new(half_width, -half_width), color);
+                let c = mesh.push_vertex(point + Vec2::new(-half_width, half_width), color);
+                let d = mesh.push_vertex(point + Vec2::new(half_width, half_width), color);
+                mesh.push_triangle(a, b, c);
+                mesh.push_triangle(c, b, d);
+            }
+            LineCap::Butt => (),
+        }
+    }
+}
+
+// Outlines all contours of the polygon, the border is placed relative to the filled area
+pub(crate) fn polygon_outline(polygon: &Polygon, border: &Border) -> Mesh2D {
+    let width = border.width as f32;
+    let outer_extent = border.outer_extent();
+
+    // With the holes wound opposite to the outer contour, the filled area is on the same side
+    // of every contour
+    let fill_side = if signed_area(&polygon.points) > 0. {
+        1.
+    } else {
+        -1.
+    };
+    let offset = (width * 0.5 - outer_extent) * fill_side;
+
+    let mut mesh = Mesh2D::new();
+    for contour in polygon.contours() {
+        let stroke = Stroke {
+            colors: vec![border.color; contour.len()],
+            points: contour,
+            thickness: width,
+            join: border.join,
+            cap: LineCap::Butt,
+            closed: true,
+            offset,
+            // The dashes are measured along the contour rather than along the middle of the
+            // border
+            style: border.style.clone(),
+        };
+        mesh.append(stroke.tessellate());
+    }
+    mesh
+}
+
+// Outlines a rect centred on the origin. The outline runs through the middle of the border,
+// where the corners are rounded the same way as the border drawn by the rect pipeline.
+pub(crate) fn rect_outline(size: Vec2, corner_radii: Vec4, border: &Border) -> Mesh2D {
+    let width = border.width as f32;
+    let center_offset = border.outer_extent() - width * 0.5;
+    let half_size = size.abs() * 0.5 + Vec2::new(center_offset, center_offset);
+    if half_size.x <= 0. || half_size.y <= 0. {
+        return Mesh2D::new();
+    }
+
+    // Corners in the order top left, top right, bottom right, bottom left, going clockwise
+    // on screen
+    let corners = [
+        Vec2::new(-1., -1.),
+        Vec2::new(1., -1.),
+        Vec2::new(1., 1.),
+        Vec2::new(-1., 1.),
+    ];
+    let mut points = Vec::new();
+    for (i, corner) in corners.iter().enumerate() {
+        let radius = (corner_radii[i] + center_offset).clamp(0., half_size.x.min(half_size.y));
+        let center = corner.component_mul(&(half_size - Vec2::new(radius, radius)));
+        let start_angle = PI + FRAC_PI_2 * i as f32;
+        if radius <= 0. {
+            points.push(center);
+            continue;
+        }
+
+        let segments = arc_segments(radius, FRAC_PI_2, ROUND_TOLERANCE);
+        for j in 0..=segments {
+            let angle = start_angle + FRAC_PI_2 * j as f32 / segments as f32;
+            points.push(center + angle_to_vec(angle) * radius);
+        }
+    }
+
+    border_stroke(points, width, border).tessellate()
+}
+
+// Outlines an ellipse centred on the origin, through the middle of the border
+pub(crate) fn ellipse_outline(radii: Vec2, border: &Border) -> Mesh2D {
+    let width = border.width as f32;
+    let center_offset = border.outer_extent() - width * 0.5;
+    let radii = radii.abs() + Vec2::new(center_offset, center_offset);
+    if radii.x <= 0. || radii.y <= 0. {
+        return Mesh2D::new();
+    }
+
+    let segments = arc_segments(radii.x.max(radii.y), TAU, ROUND_TOLERANCE).max(3);
+    let points = (0..segments)
+        .map(|i| angle_to_vec(TAU * i as f32 / segments as f32).component_mul(&radii))
+        .collect();
+
+    border_stroke(points, width, border).tessellate()
+}
+
+fn border_stroke(points: Vec<Vec2>, width: f32, border: &Border) -> Stroke {
+    Stroke {
+        colors: vec![border.color; points.len()],
+        points,
+        thickness: width,
+        join: border.join,
+        cap: LineCap::Butt,
+        closed: true,
+        offset: 0.,
+        style: border.style.clone(),
+    }
+}
+
+pub(crate) fn path_outline(sub_paths: &[SubPath], path_stroke: &PathStroke) -> Mesh2D {
+    let mut mesh = Mesh2D::new();
+    for sub_path in sub_paths {
+        let stroke = Stroke {
+            colors: vec![path_stroke.color; sub_path.points.len()],
+            points: sub_path.points.clone(),
+            thickness: path_stroke.thickness,
+            join: path_stroke.join,
+            cap: path_stroke.cap,
+            closed: sub_path.closed,
+            offset: 0.,
+            style: path_stroke.style.clone(),
+        };
+        mesh.append(stroke.tessellate());
+    }
+    mesh
+}
+
+fn add_bevel(
+    mesh: &mut Mesh2D,
+    point: Vec2,
+    color: Vec4,
+    normal_in: Vec2,
+    normal_out: Vec2,
+    half_width: f32,
+) {
+    let center = mesh.push_vertex(point, color);
+    let a = mesh.push_vertex(point + normal_in * half_width, color);
+    let b = mesh.push_vertex(point + normal_out * half_width, color);
+    mesh.push_triangle(center, a, b);
+}
+
+// Half circle around point, bulging out in the given direction
+fn add_round_cap(mesh: &mut Mesh2D, point: Vec2, color: Vec4, dir: Vec2, half_width: f32) {
+    let normal = Vec2::new(-dir.y, dir.x);
+    mesh.push_fan(point, color, half_width, normal.y.atan2(normal.x), -PI);
+}
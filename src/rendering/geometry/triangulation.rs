@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+
+use nalgebra_glm::Vec2;
+
+use crate::public::objects::obj_2d::polygon::FillRule;
+
+use super::cross_2d;
+
+const EPSILON: f32 = 0.0001;
+
+#[derive(Clone, Debug)]
+struct Edge {
+    top: Vec2,
+    bottom: Vec2,
+    // +1 for edges going down the screen, -1 for edges going up
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let height = self.bottom.y - self.top.y;
+        if height <= 0. {
+            return self.top.x;
+        }
+        let t = ((y - self.top.y) / height).clamp(0., 1.);
+        self.top.x + (self.bottom.x - self.top.x) * t
+    }
+}
+
+// Splits the area enclosed by the contours into horizontal slabs, bounded by every vertex
+// and every edge intersection. Edges can't cross inside a slab, so the inside parts of each
+// slab are trapezoids between neighbouring edges. This works for concave, self-intersecting
+// and multi-contour input (holes) alike.
+pub(crate) fn triangulate(contours: &[Vec<Vec2>], fill_rule: FillRule) -> (Vec<Vec2>, Vec<u32>) {
+    let mut edges = create_edges(contours);
+    edges.sort_by(|a, b| cmp_f32(a.top.y, b.top.y));
+
+    let mut slab_ys = edges
+        .iter()
+        .flat_map(|edge| [edge.top.y, edge.bottom.y])
+        .collect::<Vec<_>>();
+    slab_ys.extend(intersection_ys(&edges));
+    slab_ys.sort_by(|a, b| cmp_f32(*a, *b));
+    slab_ys.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    let mut active: Vec<&Edge> = Vec::new();
+    let mut next_edge = 0;
+
+    for slab in slab_ys.windows(2) {
+        let (y0, y1) = (slab[0], slab[1]);
+        let y_mid = (y0 + y1) * 0.5;
+
+        active.retain(|edge| edge.bottom.y > y_mid);
+        while next_edge < edges.len() && edges[next_edge].top.y < y_mid {
+            if edges[next_edge].bottom.y > y_mid {
+                active.push(&edges[next_edge]);
+            }
+            next_edge += 1;
+        }
+        active.sort_by(|a, b| cmp_f32(a.x_at(y_mid), b.x_at(y_mid)));
+
+        let mut winding = 0;
+        for pair in active.windows(2) {
+            winding += pair[0].winding;
+            let inside = match fill_rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+            if !inside {
+                continue;
+            }
+
+            let (left, right) = (pair[0], pair[1]);
+            let index = positions.len() as u32;
+            positions.push(Vec2::new(left.x_at(y0), y0));
+            positions.push(Vec2::new(right.x_at(y0), y0));
+            positions.push(Vec2::new(left.x_at(y1), y1));
+            positions.push(Vec2::new(right.x_at(y1), y1));
+            indices.extend_from_slice(&[index, index + 1, index + 2]);
+            indices.extend_from_slice(&[index + 2, index + 1, index + 3]);
+        }
+    }
+
+    (positions, indices)
+}
+
+// Shoelace formula, positive for contours that are clockwise on screen
+pub(crate) fn signed_area(points: &[Vec2]) -> f32 {
+    let mut area = 0.;
+    for (i, p0) in points.iter().enumerate() {
+        let p1 = &points[(i + 1) % points.len()];
+        area += cross_2d(p0, p1);
+    }
+    area * 0.5
+}
+
+fn create_edges(contours: &[Vec<Vec2>]) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for contour in contours {
+        if contour.len() < 3 {
+            continue;
+        }
+        for (i, p0) in contour.iter().enumerate() {
+            let p1 = &contour[(i + 1) % contour.len()];
+            // Horizontal edges never cross a slab, so they don't change the winding
+            if (p1.y - p0.y).abs() < EPSILON {
+                continue;
+            }
+            edges.push(if p0.y < p1.y {
+                Edge {
+                    top: p0.clone(),
+                    bottom: p1.clone(),
+                    winding: 1,
+                }
+            } else {
+                Edge {
+                    top: p1.clone(),
+                    bottom: p0.clone(),
+                    winding: -1,
+                }
+            });
+        }
+    }
+    edges
+}
+
+// Expects the edges to be sorted by their top y
+fn intersection_ys(edges: &[Edge]) -> Vec<f32> {
+    let mut ys = Vec::new();
+    for (i, a) in edges.iter().enumerate() {
+        for b in edges[i + 1..].iter() {
+            if b.top.y >= a.bottom.y {
+                break;
+            }
+
+            let r = a.bottom - a.top;
+            let s = b.bottom - b.top;
+            let denominator = cross_2d(&r, &s);
+            if denominator.abs() < EPSILON {
+                continue;
+            }
+
+            let offset = b.top - a.top;
+            let t = cross_2d(&offset, &s) / denominator;
+            let u = cross_2d(&offset, &r) / denominator;
+            if t > 0. && t < 1. && u > 0. && u < 1. {
+                ys.push(a.top.y + r.y * t);
+            }
+        }
+    }
+    ys
+}
+
+fn cmp_f32(a: f32, b: f32) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::Vec2;
+
+    use super::{signed_area, triangulate};
+    use crate::{
+        public::objects::obj_2d::polygon::{FillRule, Polygon},
+        rendering::geometry::cross_2d,
+    };
+
+    const TOLERANCE: f32 = 0.001;
+
+    fn points(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    fn triangulated_area(contours: &[Vec<Vec2>], fill_rule: FillRule) -> f32 {
+        let (positions, indices) = triangulate(contours, fill_rule);
+        indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let a = positions[triangle[0] as usize];
+                let b = positions[triangle[1] as usize];
+                let c = positions[triangle[2] as usize];
+                cross_2d(&(b - a), &(c - a)).abs() * 0.5
+            })
+            .sum()
+    }
+
+    fn assert_area(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < TOLERANCE,
+            "triangulated area {} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn convex_polygon() {
+        let hexagon = points(&[(0., 0.), (4., 0.), (6., 3.), (4., 6.), (0., 6.), (-2., 3.)]);
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert_area(
+                triangulated_area(&[hexagon.clone()], fill_rule),
+                signed_area(&hexagon).abs(),
+            );
+        }
+    }
+
+    #[test]
+    fn concave_polygons() {
+        let l_shape = points(&[(0., 0.), (2., 0.), (2., 8.), (6., 8.), (6., 10.), (0., 10.)]);
+        assert_area(
+            triangulated_area(&[l_shape.clone()], FillRule::NonZero),
+            signed_area(&l_shape).abs(),
+        );
+
+        // Star without self intersections, alternating between an outer and an inner radius
+        let star = (0..10)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::PI / 5.;
+                let radius = if i % 2 == 0 { 10. } else { 4. };
+                Vec2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect::<Vec<_>>();
+        assert_area(
+            triangulated_area(&[star.clone()], FillRule::EvenOdd),
+            signed_area(&star).abs(),
+        );
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let outer = points(&[(0., 0.), (10., 0.), (10., 10.), (0., 10.)]);
+        let hole = points(&[(2., 2.), (6., 2.), (6., 5.), (2., 5.)]);
+        let polygon = Polygon::new_with_points(outer.clone()).with_hole(hole.clone());
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert_area(
+                triangulated_area(&polygon.contours(), fill_rule),
+                signed_area(&outer).abs() - signed_area(&hole).abs(),
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_points() {
+        let outer = points(&[(0., 0.), (10., 0.), (10., 10.), (0., 10.)]);
+        let closed = points(&[
+            (0., 0.),
+            (10., 0.),
+            (10., 0.),
+            (10., 10.),
+            (0., 10.),
+            (0., 0.),
+        ]);
+        let polygon = Polygon::new_with_points(closed.clone());
+        assert_eq!(polygon.validate(), Ok(()));
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert_area(
+                triangulated_area(&polygon.contours(), fill_rule),
+                signed_area(&outer).abs(),
+            );
+            // The triangulation copes with them on its own as well, as paths aren't cleaned up
+            assert_area(
+                triangulated_area(&[closed.clone()], fill_rule),
+                signed_area(&outer).abs(),
+            );
+        }
+    }
+
+    #[test]
+    fn self_intersecting_bow_tie() {
+        let bow_tie = points(&[(0., 0.), (10., 10.), (10., 0.), (0., 10.)]);
+        // Both halves are wound once, in opposite directions, so they cancel out in the signed
+        // area of the whole contour
+        let left = points(&[(0., 0.), (5., 5.), (0., 10.)]);
+        let right = points(&[(5., 5.), (10., 0.), (10., 10.)]);
+        let expected = signed_area(&left).abs() + signed_area(&right).abs();
+        assert!(signed_area(&bow_tie).abs() < TOLERANCE);
+
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert_area(triangulated_area(&[bow_tie.clone()], fill_rule), expected);
+        }
+    }
+
+    #[test]
+    fn overlapping_contours_depend_on_fill_rule() {
+        let a = points(&[(0., 0.), (4., 0.), (4., 4.), (0., 4.)]);
+        let b = points(&[(2., 2.), (6., 2.), (6., 6.), (2., 6.)]);
+        let overlap = points(&[(2., 2.), (4., 2.), (4., 4.), (2., 4.)]);
+        let contours = [a.clone(), b.clone()];
+        let union = signed_area(&a) + signed_area(&b) - signed_area(&overlap);
+
+        assert_area(triangulated_area(&contours, FillRule::NonZero), union);
+        assert_area(
+            triangulated_area(&contours, FillRule::EvenOdd),
+            union - signed_area(&overlap),
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use fontdue::Metrics;
+use nalgebra_glm::UVec2;
+
+use crate::public::objects::obj_2d::font::FontHandle;
+
+use super::lru_cache::LruCache;
+
+const INITIAL_ATLAS_SIZE: u32 = 256;
+// Every Vulkan device supports 2D images of at least this size
+const MAX_ATLAS_SIZE: u32 = 4096;
+// Glyphs without pixels never fill the atlas, so it is also repacked when it holds this many
+const MAX_CACHED_GLYPHS: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct GlyphKey {
+    pub(crate) font: FontHandle,
+    // Bits of the size in pixels, as floats can't be hashed
+    pub(crate) size: u32,
+    pub(crate) c: char,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AtlasGlyph {
+    pub(crate) metrics: Metrics,
+    // Top left texel of the glyph, None for glyphs without pixels
+    pub(crate) position: Option<UVec2>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    // Where the next glyph on this shelf goes
+    x: u32,
+}
+
+// Rasterized glyphs packed into rows of a single square R8 image
+pub(crate) struct GlyphAtlas {
+    size: u32,
+    pixels: Vec<u8>,
+    shelves: Vec<Shelf>,
+    glyphs: LruCache<GlyphKey, AtlasGlyph>,
+    // Regions written since the last upload, given as position and size
+    dirty_regions: Vec<(UVec2, UVec2)>,
+    // Set when the image has to be created again because it grew or was repacked
+    resized: bool,
+    // Changes whenever glyphs are moved, previously returned positions are invalid after that
+    generation: u64,
+}
+
+impl GlyphAtlas {
+    pub(crate) fn new() -> Self {
+        Self {
+            size: INITIAL_ATLAS_SIZE,
+            pixels: vec![0; (INITIAL_ATLAS_SIZE * INITIAL_ATLAS_SIZE) as usize],
+            shelves: Vec::new(),
+            // Never full, glyphs are only dropped by repacking so their pixels go with them
+            glyphs: LruCache::new(usize::MAX),
+            dirty_regions: Vec::new(),
+            resized: true,
+            generation: 0,
+        }
+    }
+
+    pub(crate) fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub(crate) fn get(&mut self, key: &GlyphKey) -> Option<AtlasGlyph> {
+        self.glyphs.get(key).copied()
+    }
+
+    // Returns None when the glyph doesn't fit, even in an empty atlas of the maximum size
+    pub(crate) fn insert(
+        &mut self,
+        key: GlyphKey,
+        metrics: Metrics,
+        bitmap: &[u8],
+    ) -> Option<AtlasGlyph> {
+        if self.glyphs.len() >= MAX_CACHED_GLYPHS {
+            self.repack();
+        }
+
+        let glyph_size = UVec2::new(metrics.width as u32, metrics.height as u32);
+        if glyph_size.x == 0 || glyph_size.y == 0 {
+            let glyph = AtlasGlyph {
+                metrics,
+                position: None,
+            };
+            self.glyphs.insert(key, glyph);
+            return Some(glyph);
+        }
+
+        if glyph_size.x > MAX_ATLAS_SIZE || glyph_size.y > MAX_ATLAS_SIZE {
+            return None;
+        }
+
+        let mut repacked = false;
+        let position = loop {
+            if let Some(position) = allocate(&mut self.shelves, self.size, glyph_size) {
+                break position;
+            }
+            if self.size < MAX_ATLAS_SIZE {
+                self.grow();
+            } else if !repacked {
+                self.repack();
+                repacked = true;
+            } else {
+                return None;
+            }
+        };
+
+        self.write(position, glyph_size, bitmap);
+        let glyph = AtlasGlyph {
+            metrics,
+            position: Some(position),
+        };
+        self.glyphs.insert(key, glyph);
+        Some(glyph)
+    }
+
+    // Returns whether the image has to be created again, otherwise only the returned regions
+    // have to be uploaded
+    pub(crate) fn take_changes(&mut self) -> (bool, Vec<(UVec2, UVec2)>) {
+        let resized = self.resized;
+        self.resized = false;
+        (resized, std::mem::take(&mut self.dirty_regions))
+    }
+
+    // Copies a region of the atlas row by row
+    pub(crate) fn region(&self, position: UVec2, size: UVec2) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity((size.x * size.y) as usize);
+        for y in position.y..position.y + size.y {
+            let start = (y * self.size + position.x) as usize;
+            bytes.extend_from_slice(&self.pixels[start..start + size.x as usize]);
+        }
+        bytes
+    }
+
+    // Glyphs keep their positions, so the shelves only get wider
+    fn grow(&mut self) {
+        let size = (self.size * 2).min(MAX_ATLAS_SIZE);
+        let mut pixels = vec![0; (size * size) as usize];
+        for y in 0..self.size {
+            let old_start = (y * self.size) as usize;
+            let new_start = (y * size) as usize;
+            pixels[new_start..new_start + self.size as usize]
+                .copy_from_slice(&self.pixels[old_start..old_start + self.size as usize]);
+        }
+        self.size = size;
+        self.pixels = pixels;
+        self.resized = true;
+    }
+
+    // Drops the least recently used half of the glyphs and packs the rest again
+    fn repack(&mut self) {
+        let old_pixels =
+            std::mem::replace(&mut self.pixels, vec![0; (self.size * self.size) as usize]);
+        self.shelves.clear();
+        self.glyphs.truncate(self.glyphs.len() / 2);
+
+        // Packing the tallest glyphs first wastes the least space on the shelves
+        let mut glyphs = self
+            .glyphs
+            .iter_mut()
+            .filter(|(_, glyph)| glyph.position.is_some())
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(_, glyph)| std::cmp::Reverse(glyph.metrics.height));
+
+        let mut dropped = Vec::new();
+        for (key, glyph) in glyphs {
+            let old_position = glyph.position.unwrap();
+            let glyph_size = UVec2::new(glyph.metrics.width as u32, glyph.metrics.height as u32);
+            let position = match allocate(&mut self.shelves, self.size, glyph_size) {
+                Some(position) => position,
+                None => {
+                    dropped.push(*key);
+                    continue;
+                }
+            };
+            for y in 0..glyph_size.y {
+                let old_start = ((old_position.y + y) * self.size + old_position.x) as usize;
+                let new_start = ((position.y + y) * self.size + position.x) as usize;
+                self.pixels[new_start..new_start + glyph_size.x as usize]
+                    .copy_from_slice(&old_pixels[old_start..old_start + glyph_size.x as usize]);
+            }
+            glyph.position = Some(position);
+        }
+        for key in dropped {
+            self.glyphs.remove(&key);
+        }
+
+        self.dirty_regions.clear();
+        self.resized = true;
+        self.generation += 1;
+    }
+
+    fn write(&mut self, position: UVec2, glyph_size: UVec2, bitmap: &[u8]) {
+        for y in 0..glyph_size.y {
+            let start = ((position.y + y) * self.size + position.x) as usize;
+            let row = (y * glyph_size.x) as usize;
+            self.pixels[start..start + glyph_size.x as usize]
+                .copy_from_slice(&bitmap[row..row + glyph_size.x as usize]);
+        }
+        if !self.resized {
+            self.dirty_regions.push((position, glyph_size));
+        }
+    }
+}
+
+// Finds room on the lowest shelf that fits, or starts a new shelf below the others
+fn allocate(shelves: &mut Vec<Shelf>, atlas_size: u32, glyph_size: UVec2) -> Option<UVec2> {
+    let shelf = shelves
+        .iter_mut()
+        .filter(|shelf| shelf.height >= glyph_size.y && atlas_size - shelf.x >= glyph_size.x)
+        .min_by_key(|shelf| shelf.height);
+    if let Some(shelf) = shelf {
+        let position = UVec2::new(shelf.x, shelf.y);
+        shelf.x += glyph_size.x;
+        return Some(position);
+    }
+
+    let y = shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+    if glyph_size.x > atlas_size || glyph_size.y > atlas_size - y {
+        return None;
+    }
+    shelves.push(Shelf {
+        y,
+        height: glyph_size.y,
+        x: glyph_size.x,
+    });
+    Some(UVec2::new(0, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::public::objects::obj_2d::font::FontRegistry;
+
+    fn font() -> FontHandle {
+        FontRegistry::new()
+            .load_bytes(include_bytes!(
+                "../../assets/fonts/Roboto/Roboto-Regular.ttf"
+            ))
+            .unwrap()
+    }
+
+    fn key(font: FontHandle, i: u32) -> GlyphKey {
+        GlyphKey {
+            font,
+            size: 0,
+            c: char::from_u32('a' as u32 + i).unwrap(),
+        }
+    }
+
+    fn metrics(size: u32) -> Metrics {
+        Metrics {
+            width: size as usize,
+            height: size as usize,
+            ..Default::default()
+        }
+    }
+
+    // Every pixel of a glyph has the value of its index, so moved glyphs can be checked
+    fn insert(atlas: &mut GlyphAtlas, font: FontHandle, i: u32, size: u32) -> AtlasGlyph {
+        let bitmap = vec![i as u8 + 1; (size * size) as usize];
+        atlas.insert(key(font, i), metrics(size), &bitmap).unwrap()
+    }
+
+    fn assert_pixels(atlas: &GlyphAtlas, glyph: AtlasGlyph, i: u32) {
+        let size = UVec2::new(glyph.metrics.width as u32, glyph.metrics.height as u32);
+        let pixels = atlas.region(glyph.position.unwrap(), size);
+        assert!(pixels.iter().all(|&pixel| pixel == i as u8 + 1));
+    }
+
+    #[test]
+    fn shelves_are_reused() {
+        let mut shelves = Vec::new();
+        assert_eq!(
+            allocate(&mut shelves, 64, UVec2::new(10, 10)),
+            Some(UVec2::new(0, 0))
+        );
+        // Lower glyphs go next to the others, higher ones start a new shelf
+        assert_eq!(
+            allocate(&mut shelves, 64, UVec2::new(10, 8)),
+            Some(UVec2::new(10, 0))
+        );
+        assert_eq!(
+            allocate(&mut shelves, 64, UVec2::new(10, 12)),
+            Some(UVec2::new(0, 10))
+        );
+        // The lowest shelf the glyph fits on is used
+        assert_eq!(
+            allocate(&mut shelves, 64, UVec2::new(10, 9)),
+            Some(UVec2::new(20, 0))
+        );
+        assert_eq!(allocate(&mut shelves, 64, UVec2::new(10, 50)), None);
+        assert_eq!(allocate(&mut shelves, 64, UVec2::new(65, 1)), None);
+    }
+
+    #[test]
+    fn growing_keeps_positions() {
+        let font = font();
+        let mut atlas = GlyphAtlas::new();
+        // 64 glyphs fill the initial size
+        let glyphs = (0..65)
+            .map(|i| insert(&mut atlas, font, i, 32))
+            .collect::<Vec<_>>();
+
+        assert_eq!(atlas.size(), INITIAL_ATLAS_SIZE * 2);
+        assert_eq!(atlas.generation(), 0);
+        for (i, glyph) in glyphs.into_iter().enumerate() {
+            let cached = atlas.get(&key(font, i as u32)).unwrap();
+            assert_eq!(cached.position, glyph.position);
+            assert_pixels(&atlas, cached, i as u32);
+        }
+    }
+
+    #[test]
+    fn repacking_keeps_most_recently_used_half() {
+        let font = font();
+        let mut atlas = GlyphAtlas::new();
+        for i in 0..8 {
+            insert(&mut atlas, font, i, 16 + i);
+        }
+        for i in 0..4 {
+            atlas.get(&key(font, i));
+        }
+        atlas.take_changes();
+
+        atlas.repack();
+
+        assert_eq!(atlas.generation(), 1);
+        assert_eq!(atlas.glyphs.len(), 4);
+        assert!(atlas.take_changes().0);
+        for i in 0..4 {
+            let glyph = atlas.get(&key(font, i)).unwrap();
+            assert_pixels(&atlas, glyph, i);
+        }
+        for i in 4..8 {
+            assert!(atlas.get(&key(font, i)).is_none());
+        }
+    }
+
+    #[test]
+    fn full_atlas_is_repacked() {
+        let font = font();
+        let mut atlas = GlyphAtlas::new();
+        // 64 glyphs fill the maximum size
+        for i in 0..65 {
+            insert(&mut atlas, font, i, MAX_ATLAS_SIZE / 8);
+        }
+
+        assert_eq!(atlas.size(), MAX_ATLAS_SIZE);
+        assert_eq!(atlas.generation(), 1);
+        assert_eq!(atlas.glyphs.len(), 33);
+        for i in 32..65 {
+            let glyph = atlas.get(&key(font, i)).unwrap();
+            assert_pixels(&atlas, glyph, i);
+        }
+    }
+
+    #[test]
+    fn too_many_glyphs_are_repacked() {
+        let font = font();
+        let mut atlas = GlyphAtlas::new();
+        for i in 0..MAX_CACHED_GLYPHS as u32 + 1 {
+            atlas.insert(key(font, i), metrics(0), &[]).unwrap();
+        }
+
+        assert_eq!(atlas.generation(), 1);
+        assert_eq!(atlas.glyphs.len(), MAX_CACHED_GLYPHS / 2 + 1);
+    }
+}
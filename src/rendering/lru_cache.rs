@@ -0,0 +1,124 @@
+use std::{collections::HashMap, hash::Hash};
+
+// Map that drops the least recently used entry once it holds more than its capacity
+#[derive(Clone, Debug)]
+pub(crate) struct LruCache<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    capacity: usize,
+    // Incremented on every access, entries remember when they were used last
+    clock: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(key).map(|(value, last_used)| {
+            *last_used = clock;
+            &*value
+        })
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            // Evicting only happens on a miss, which is a lot more expensive than this search
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (value, self.clock));
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key).map(|(value, _)| value)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Keeps only the len most recently used entries
+    pub(crate) fn truncate(&mut self, len: usize) {
+        if self.entries.len() <= len {
+            return;
+        }
+        if len == 0 {
+            self.entries.clear();
+            return;
+        }
+
+        // Every access has its own time, so exactly len entries are kept
+        let mut last_used = self
+            .entries
+            .values()
+            .map(|(_, last_used)| *last_used)
+            .collect::<Vec<_>>();
+        last_used.sort_unstable_by(|a, b| b.cmp(a));
+        let oldest_kept = last_used[len - 1];
+        self.entries
+            .retain(|_, (_, last_used)| *last_used >= oldest_kept);
+    }
+
+    // Iterates without counting as a use
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.entries
+            .iter_mut()
+            .map(|(key, (value, _))| (key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, 'a');
+        cache.insert(2, 'b');
+        cache.get(&1);
+        cache.insert(3, 'c');
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&1), Some(&'a'));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some(&'c'));
+    }
+
+    #[test]
+    fn truncate_keeps_exactly_len_entries() {
+        let mut cache = LruCache::new(100);
+        for i in 0..10 {
+            cache.insert(i, i);
+        }
+        for i in [2, 7, 4] {
+            cache.get(&i);
+        }
+
+        cache.truncate(20);
+        assert_eq!(cache.len(), 10);
+
+        cache.truncate(4);
+        assert_eq!(cache.len(), 4);
+        for i in [9, 2, 7, 4] {
+            assert_eq!(cache.get(&i), Some(&i));
+        }
+
+        cache.truncate(0);
+        assert_eq!(cache.len(), 0);
+    }
+}
@@ -1,4 +1,7 @@
+pub(crate) mod geometry;
+pub(crate) mod glyph_atlas;
+pub(crate) mod lru_cache;
 pub(crate) mod pipelines;
-mod render_objects;
+pub(crate) mod render_objects;
 //TODO: See if this can be made private
 pub(crate) mod render_containers;
\ No newline at end of file
@@ -13,15 +13,18 @@ use vulkano::{
             vertex_input::BuffersDefinition,
             viewport::{Viewport, ViewportState},
         },
-        GraphicsPipeline, Pipeline,
+        GraphicsPipeline, Pipeline, PipelineBindPoint,
     },
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
     shader::ShaderModule,
 };
 
-use crate::rendering::{
-    render_containers::device_container::DeviceContainer,
-    render_objects::shared::{BufferContainer2D, Vertex2D},
+use crate::{
+    public::objects::fill::Fill,
+    rendering::{
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{BufferContainer2D, FillSets, Vertex2D},
+    },
 };
 
 pub(crate) mod circle_vs {
@@ -40,6 +43,7 @@ pub(crate) mod circle_fs {
     vulkano_shaders::shader!(
         ty: "fragment",
         path: "src/shaders/shaders_2d/circle_pipeline.frag",
+        include: ["src/shaders/shaders_2d"],
         types_meta: {
             use bytemuck::{Pod, Zeroable};
 
@@ -52,6 +56,7 @@ pub(crate) struct CirclePipeline {
     vs: Arc<ShaderModule>,
     fs: Arc<ShaderModule>,
     pipeline: Arc<GraphicsPipeline>,
+    fill_sets: FillSets,
 }
 
 impl CirclePipeline {
@@ -60,8 +65,14 @@ impl CirclePipeline {
         let fs = circle_fs::load(device_container.device().clone()).unwrap();
 
         let (pipeline) = Self::create_pipeline(device_container, &vs, &fs);
+        let fill_sets = Self::create_fill_sets(device_container, &pipeline);
 
-        Self { pipeline, vs, fs }
+        Self {
+            pipeline,
+            vs,
+            fs,
+            fill_sets,
+        }
     }
 
     fn create_pipeline(
@@ -87,8 +98,19 @@ impl CirclePipeline {
             .unwrap()
     }
 
+    fn create_fill_sets(
+        device_container: &DeviceContainer,
+        pipeline: &Arc<GraphicsPipeline>,
+    ) -> FillSets {
+        FillSets::new(
+            device_container,
+            pipeline.layout().set_layouts().get(0).unwrap(),
+        )
+    }
+
     pub(crate) fn recreate_pipeline(&mut self, device_container: &DeviceContainer) {
         self.pipeline = Self::create_pipeline(device_container, &self.vs, &self.fs);
+        self.fill_sets = Self::create_fill_sets(device_container, &self.pipeline);
     }
 
     pub(crate) fn draw(
@@ -96,11 +118,24 @@ impl CirclePipeline {
         device_container: &mut DeviceContainer,
         buffers: &BufferContainer2D,
         push_constants: circle_fs::ty::Constants,
+        fill: Option<&Fill>,
     ) {
+        let fill_set = self.fill_sets.get(
+            device_container,
+            self.pipeline.layout().set_layouts().get(0).unwrap(),
+            fill,
+        );
+
         let mut builder = device_container.get_command_buffer_builder();
 
         builder
             .bind_pipeline_graphics(self.pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                fill_set,
+            )
             .bind_vertex_buffers(0, buffers.vertex_buffer.clone())
             .bind_index_buffer(buffers.index_buffer.clone())
             .push_constants(self.pipeline.layout().clone(), 0, push_constants)
@@ -1,11 +1,19 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
+use nalgebra_glm::UVec2;
 use vulkano::{
-    buffer::TypedBufferAccess,
+    buffer::{BufferUsage, CpuAccessibleBuffer, TypedBufferAccess},
     command_buffer::{
-        AutoCommandBufferBuilder, CommandBufferUsage, RenderPassBeginInfo, SubpassContents,
+        AutoCommandBufferBuilder, BufferImageCopy, CommandBufferUsage, CopyBufferToImageInfo,
+        RenderPassBeginInfo, SubpassContents,
+    },
+    descriptor_set::{PersistentDescriptorSet, WriteDescriptorSet},
+    format::Format,
+    image::{
+        view::{ImageView, ImageViewAbstract},
+        ImageAccess, ImageCreateFlags, ImageDimensions, ImageUsage, ImmutableImage, MipmapsCount,
+        StorageImage,
     },
-    image::view::ImageView,
     pipeline::{
         graphics::{
             color_blend::ColorBlendState,
@@ -13,15 +21,21 @@ use vulkano::{
             vertex_input::BuffersDefinition,
             viewport::{Viewport, ViewportState},
         },
-        GraphicsPipeline, Pipeline,
+        GraphicsPipeline, Pipeline, PipelineBindPoint,
     },
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
+    sampler::{
+        Filter, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode, LOD_CLAMP_NONE,
+    },
     shader::ShaderModule,
 };
 
-use crate::rendering::{
-    render_containers::device_container::DeviceContainer,
-    render_objects::shared::{BufferContainer2D, BufferContainer2DUv, Vertex2D},
+use crate::{
+    public::objects::obj_2d::figure::{FigureImage, FilterMode, WeakFigureImage, WrapMode},
+    rendering::{
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{BufferContainer2DUvColor, Vertex2DUvColor},
+    },
 };
 
 pub(crate) mod figure_vs {
@@ -48,10 +62,21 @@ pub(crate) mod figure_fs {
     }
 }
 
+struct UploadedImage {
+    image: WeakFigureImage,
+    view: Arc<dyn ImageViewAbstract>,
+    // Mipmapped images can't be partially updated
+    storage_image: Option<Arc<StorageImage>>,
+    sets: HashMap<(FilterMode, WrapMode), Arc<PersistentDescriptorSet>>,
+}
+
 pub(crate) struct FigurePipeline {
     vs: Arc<ShaderModule>,
     fs: Arc<ShaderModule>,
     pipeline: Arc<GraphicsPipeline>,
+    // Uploaded images by id
+    uploaded_images: HashMap<u64, UploadedImage>,
+    samplers: HashMap<(FilterMode, WrapMode), Arc<Sampler>>,
 }
 
 impl FigurePipeline {
@@ -61,7 +86,13 @@ impl FigurePipeline {
 
         let pipeline = Self::create_pipeline(device_container, &vs, &fs);
 
-        Self { vs, fs, pipeline }
+        Self {
+            vs,
+            fs,
+            pipeline,
+            uploaded_images: HashMap::new(),
+            samplers: HashMap::new(),
+        }
     }
 
     fn create_pipeline(
@@ -73,7 +104,7 @@ impl FigurePipeline {
             .color_blend_state(ColorBlendState::blend_alpha(ColorBlendState::new(1)))
             .render_pass(Subpass::from(device_container.render_pass().clone(), 0).unwrap())
             .input_assembly_state(InputAssemblyState::new())
-            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2D>())
+            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2DUvColor>())
             .vertex_shader(vs.entry_point("main").unwrap(), ())
             .viewport_state(ViewportState::viewport_fixed_scissor_irrelevant([
                 Viewport {
@@ -91,16 +122,143 @@ impl FigurePipeline {
         self.pipeline = Self::create_pipeline(device_container, &self.vs, &self.fs)
     }
 
+    // Uploads the image the first time it is used and the regions that changed after that,
+    // so this has to be called before drawing starts
+    pub(crate) fn get_or_create_set(
+        &mut self,
+        device_container: &mut DeviceContainer,
+        image: &FigureImage,
+        filter: FilterMode,
+        wrap: WrapMode,
+        mipmaps: bool,
+    ) -> Option<Arc<PersistentDescriptorSet>> {
+        if image.size.x == 0 || image.size.y == 0 {
+            return None;
+        }
+
+        let dirty_regions = image.take_dirty_regions();
+        let uploaded = self.uploaded_images.get(&image.id());
+        let has_mipmaps = uploaded.map_or(false, |uploaded| uploaded.storage_image.is_none());
+
+        let upload = match uploaded {
+            Some(_) if mipmaps && !has_mipmaps => true,
+            Some(uploaded) => match &uploaded.storage_image {
+                Some(storage_image) => {
+                    for (position, size) in dirty_regions {
+                        upload_region(device_container, storage_image, image, position, size);
+                    }
+                    false
+                }
+                // The mipmaps have to be generated again, so the whole image is uploaded
+                None => !dirty_regions.is_empty(),
+            },
+            None => true,
+        };
+
+        if upload {
+            let uploaded = upload_image(device_container, image, mipmaps || has_mipmaps);
+            self.uploaded_images.insert(image.id(), uploaded);
+        }
+
+        let key = (filter, wrap);
+        let sampler = self
+            .samplers
+            .entry(key)
+            .or_insert_with(|| create_sampler(device_container, filter, wrap))
+            .clone();
+        let layout = self.pipeline.layout().set_layouts().get(0).unwrap().clone();
+        let uploaded = self.uploaded_images.get_mut(&image.id()).unwrap();
+
+        let set = uploaded.sets.entry(key).or_insert_with(|| {
+            PersistentDescriptorSet::new(
+                device_container.descriptor_set_allocator().as_ref(),
+                layout,
+                [WriteDescriptorSet::image_view_sampler(
+                    0,
+                    uploaded.view.clone(),
+                    sampler,
+                )],
+            )
+            .unwrap()
+        });
+        Some(set.clone())
+    }
+
+    // Frees the images of which every figure, image and texture handle has been dropped
+    pub(crate) fn free_unused_images(&mut self) {
+        self.uploaded_images
+            .retain(|_, uploaded| uploaded.image.is_alive());
+    }
+
+    // The vertices are created every frame since figures are batched while drawing,
+    // so they go in cpu accessible buffers
     pub(crate) fn draw(
         &mut self,
         device_container: &mut DeviceContainer,
-        buffers: &BufferContainer2DUv,
+        vertices: Vec<Vertex2DUvColor>,
+        indices: Vec<u32>,
         push_constants: figure_fs::ty::Constants,
+        set: Arc<PersistentDescriptorSet>,
     ) {
+        let memory_allocator = device_container.memory_allocator();
+
+        let vertex_buffer = CpuAccessibleBuffer::from_iter(
+            memory_allocator.as_ref(),
+            BufferUsage {
+                vertex_buffer: true,
+                ..Default::default()
+            },
+            false,
+            vertices,
+        )
+        .unwrap();
+
+        let index_buffer = CpuAccessibleBuffer::from_iter(
+            memory_allocator.as_ref(),
+            BufferUsage {
+                index_buffer: true,
+                ..Default::default()
+            },
+            false,
+            indices,
+        )
+        .unwrap();
+
         let mut builder = device_container.get_command_buffer_builder();
 
         builder
             .bind_pipeline_graphics(self.pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .bind_vertex_buffers(0, vertex_buffer.clone())
+            .bind_index_buffer(index_buffer.clone())
+            .push_constants(self.pipeline.layout().clone(), 0, push_constants)
+            .draw_indexed(index_buffer.len() as u32, 1, 0, 0, 0)
+            .unwrap();
+    }
+
+    // For meshes that stay the same over several frames, like the chunks of a tilemap
+    pub(crate) fn draw_buffers(
+        &mut self,
+        device_container: &mut DeviceContainer,
+        buffers: &BufferContainer2DUvColor,
+        push_constants: figure_fs::ty::Constants,
+        set: Arc<PersistentDescriptorSet>,
+    ) {
+        let mut builder = device_container.get_command_buffer_builder();
+
+        builder
+            .bind_pipeline_graphics(self.pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                set,
+            )
             .bind_vertex_buffers(0, buffers.vertex_buffer.clone())
             .bind_index_buffer(buffers.index_buffer.clone())
             .push_constants(self.pipeline.layout().clone(), 0, push_constants)
@@ -108,3 +266,135 @@ impl FigurePipeline {
             .unwrap();
     }
 }
+
+// Mipmapped images are immutable, others are storage images so they can be updated later
+fn upload_image(
+    device_container: &mut DeviceContainer,
+    image: &FigureImage,
+    mipmaps: bool,
+) -> UploadedImage {
+    let dimensions = ImageDimensions::Dim2d {
+        width: image.size.x,
+        height: image.size.y,
+        array_layers: 1,
+    };
+
+    let (view, storage_image) = if mipmaps {
+        let memory_allocator = device_container.memory_allocator();
+        let immutable_image = ImmutableImage::from_iter(
+            memory_allocator.as_ref(),
+            image.rgba_bytes(),
+            dimensions,
+            MipmapsCount::Log2,
+            Format::R8G8B8A8_UNORM,
+            device_container.get_command_buffer_builder(),
+        )
+        .unwrap();
+
+        let view: Arc<dyn ImageViewAbstract> = ImageView::new_default(immutable_image).unwrap();
+        (view, None)
+    } else {
+        let storage_image = StorageImage::with_usage(
+            device_container.memory_allocator().as_ref(),
+            dimensions,
+            Format::R8G8B8A8_UNORM,
+            ImageUsage {
+                sampled: true,
+                transfer_dst: true,
+                ..Default::default()
+            },
+            ImageCreateFlags::empty(),
+            [device_container.queue_family_index()],
+        )
+        .unwrap();
+
+        upload_region(
+            device_container,
+            &storage_image,
+            image,
+            UVec2::new(0, 0),
+            image.size,
+        );
+
+        let view: Arc<dyn ImageViewAbstract> =
+            ImageView::new_default(storage_image.clone()).unwrap();
+        (view, Some(storage_image))
+    };
+
+    UploadedImage {
+        image: image.downgrade(),
+        view,
+        storage_image,
+        sets: HashMap::new(),
+    }
+}
+
+fn create_sampler(
+    device_container: &DeviceContainer,
+    filter: FilterMode,
+    wrap: WrapMode,
+) -> Arc<Sampler> {
+    let (filter, mipmap_mode) = match filter {
+        FilterMode::Nearest => (Filter::Nearest, SamplerMipmapMode::Nearest),
+        FilterMode::Linear => (Filter::Linear, SamplerMipmapMode::Linear),
+    };
+
+    let address_mode = match wrap {
+        WrapMode::Clamp => SamplerAddressMode::ClampToEdge,
+        WrapMode::Repeat => SamplerAddressMode::Repeat,
+        WrapMode::Mirror => SamplerAddressMode::MirroredRepeat,
+    };
+
+    Sampler::new(
+        device_container.device().clone(),
+        SamplerCreateInfo {
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_mode,
+            address_mode: [address_mode; 3],
+            lod: 0.0..=LOD_CLAMP_NONE,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+// Copies a region of the image into the same region of the gpu image
+fn upload_region(
+    device_container: &mut DeviceContainer,
+    gpu_image: &Arc<StorageImage>,
+    image: &FigureImage,
+    position: UVec2,
+    size: UVec2,
+) {
+    let size = size.inf(&(image.size - position.inf(&image.size)));
+    if size.x == 0 || size.y == 0 {
+        return;
+    }
+
+    let staging_buffer = CpuAccessibleBuffer::from_iter(
+        device_container.memory_allocator().as_ref(),
+        BufferUsage {
+            transfer_src: true,
+            ..Default::default()
+        },
+        false,
+        image.rgba_region(position, size),
+    )
+    .unwrap();
+
+    let region = BufferImageCopy {
+        image_subresource: gpu_image.subresource_layers(),
+        image_offset: [position.x, position.y, 0],
+        image_extent: [size.x, size.y, 1],
+        ..Default::default()
+    };
+
+    device_container
+        .get_command_buffer_builder()
+        .copy_buffer_to_image(CopyBufferToImageInfo {
+            regions: [region].into(),
+            ..CopyBufferToImageInfo::buffer_image(staging_buffer, gpu_image.clone())
+        })
+        .unwrap();
+}
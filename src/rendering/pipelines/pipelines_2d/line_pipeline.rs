@@ -20,7 +20,7 @@ use vulkano::{
 
 use crate::rendering::{
     render_containers::device_container::DeviceContainer,
-    render_objects::shared::{BufferContainer2D, Vertex2D},
+    render_objects::shared::{BufferContainer2DColor, Vertex2DColor},
 };
 
 // TODO: Implement recreate pipeline
@@ -74,7 +74,7 @@ impl LinePipeline {
             .color_blend_state(ColorBlendState::blend_alpha(ColorBlendState::new(1)))
             .render_pass(Subpass::from(device_container.render_pass().clone(), 0).unwrap())
             .input_assembly_state(InputAssemblyState::new())
-            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2D>())
+            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2DColor>())
             .vertex_shader(vs.entry_point("main").unwrap(), ())
             .viewport_state(ViewportState::viewport_fixed_scissor_irrelevant([
                 Viewport {
@@ -95,7 +95,7 @@ impl LinePipeline {
     pub(crate) fn draw(
         &mut self,
         device_container: &mut DeviceContainer,
-        buffers: &BufferContainer2D,
+        buffers: &BufferContainer2DColor,
         push_constants: line_fs::ty::Constants,
     ) {
         let mut builder = device_container.get_command_buffer_builder();
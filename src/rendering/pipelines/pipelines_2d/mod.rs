@@ -1,5 +1,8 @@
 pub(crate) mod circle_pipeline;
 pub(crate) mod figure_pipeline;
 pub(crate) mod line_pipeline;
+pub(crate) mod particle_pipeline;
 pub(crate) mod poly_pipeline;
+pub(crate) mod rect_pipeline;
+pub(crate) mod shadow_pipeline;
 pub(crate) mod text_pipeline;
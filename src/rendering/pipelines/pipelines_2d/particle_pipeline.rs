@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use nalgebra_glm::UVec2;
+
+use vulkano::{
+    buffer::{BufferUsage, CpuAccessibleBuffer, TypedBufferAccess},
+    descriptor_set::PersistentDescriptorSet,
+    pipeline::{
+        graphics::{
+            color_blend::{AttachmentBlend, BlendFactor, BlendOp, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            vertex_input::BuffersDefinition,
+            viewport::{Viewport, ViewportState},
+        },
+        GraphicsPipeline, Pipeline, PipelineBindPoint,
+    },
+    render_pass::Subpass,
+    shader::ShaderModule,
+};
+
+use crate::{
+    public::objects::obj_2d::{
+        figure::{FigureImage, FigureImageFormat},
+        particles::BlendMode,
+    },
+    rendering::{
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{ParticleInstance, Vertex2D},
+    },
+};
+
+pub(crate) mod particle_vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        path: "src/shaders/shaders_2d/particle_pipeline.vert",
+        types_meta: {
+            use bytemuck::{Pod, Zeroable};
+
+            #[derive(Clone, Copy, Zeroable, Pod)]
+        }
+    }
+}
+
+pub(crate) mod particle_fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        path: "src/shaders/shaders_2d/particle_pipeline.frag",
+        types_meta: {
+            use bytemuck::{Pod, Zeroable};
+
+            #[derive(Clone, Copy, Zeroable, Pod)]
+        }
+    }
+}
+
+// Has to match the shapes in the particle fragment shader
+pub(crate) const PARTICLE_SHAPE_CIRCLE: u32 = 0;
+pub(crate) const PARTICLE_SHAPE_QUAD: u32 = 1;
+
+// Draws every particle of an emitter as an instance of one quad.
+// The descriptor sets come from the figure pipeline, which has the same set layout.
+pub(crate) struct ParticlePipeline {
+    vs: Arc<ShaderModule>,
+    fs: Arc<ShaderModule>,
+    alpha_pipeline: Arc<GraphicsPipeline>,
+    additive_pipeline: Arc<GraphicsPipeline>,
+    quad_vertices: Arc<CpuAccessibleBuffer<[Vertex2D]>>,
+    quad_indices: Arc<CpuAccessibleBuffer<[u32]>>,
+    // Sampled by circle and rect particles
+    pub(crate) white_image: FigureImage,
+}
+
+impl ParticlePipeline {
+    pub(crate) fn new(device_container: &DeviceContainer) -> Self {
+        let vs = particle_vs::load(device_container.device().clone()).unwrap();
+        let fs = particle_fs::load(device_container.device().clone()).unwrap();
+
+        let alpha_pipeline = Self::create_pipeline(device_container, &vs, &fs, BlendMode::Alpha);
+        let additive_pipeline =
+            Self::create_pipeline(device_container, &vs, &fs, BlendMode::Additive);
+
+        let memory_allocator = device_container.memory_allocator();
+        let quad_vertices = CpuAccessibleBuffer::from_iter(
+            memory_allocator.as_ref(),
+            BufferUsage {
+                vertex_buffer: true,
+                ..Default::default()
+            },
+            false,
+            [[-0.5, -0.5], [0.5, -0.5], [-0.5, 0.5], [0.5, 0.5]]
+                .map(|position| Vertex2D { position }),
+        )
+        .unwrap();
+
+        let quad_indices = CpuAccessibleBuffer::from_iter(
+            memory_allocator.as_ref(),
+            BufferUsage {
+                index_buffer: true,
+                ..Default::default()
+            },
+            false,
+            [0, 1, 2, 2, 1, 3],
+        )
+        .unwrap();
+
+        Self {
+            vs,
+            fs,
+            alpha_pipeline,
+            additive_pipeline,
+            quad_vertices,
+            quad_indices,
+            white_image: FigureImage::new(UVec2::new(1, 1), vec![255; 4], FigureImageFormat::Rgba),
+        }
+    }
+
+    fn create_pipeline(
+        device_container: &DeviceContainer,
+        vs: &Arc<ShaderModule>,
+        fs: &Arc<ShaderModule>,
+        blend: BlendMode,
+    ) -> Arc<GraphicsPipeline> {
+        let attachment_blend = match blend {
+            BlendMode::Alpha => AttachmentBlend::alpha(),
+            // Like AttachmentBlend::additive, but fading particles out with alpha still works
+            BlendMode::Additive => AttachmentBlend {
+                color_op: BlendOp::Add,
+                color_source: BlendFactor::SrcAlpha,
+                color_destination: BlendFactor::One,
+                alpha_op: BlendOp::Max,
+                alpha_source: BlendFactor::One,
+                alpha_destination: BlendFactor::One,
+            },
+        };
+
+        GraphicsPipeline::start()
+            .color_blend_state(ColorBlendState::new(1).blend(attachment_blend))
+            .render_pass(Subpass::from(device_container.render_pass().clone(), 0).unwrap())
+            .input_assembly_state(InputAssemblyState::new())
+            .vertex_input_state(
+                BuffersDefinition::new()
+                    .vertex::<Vertex2D>()
+                    .instance::<ParticleInstance>(),
+            )
+            .vertex_shader(vs.entry_point("main").unwrap(), ())
+            .viewport_state(ViewportState::viewport_fixed_scissor_irrelevant([
+                Viewport {
+                    origin: [0.0, 0.0],
+                    dimensions: device_container.resolution_f32(),
+                    depth_range: 0.0..1.0,
+                },
+            ]))
+            .fragment_shader(fs.entry_point("main").unwrap(), ())
+            .build(device_container.device().clone())
+            .unwrap()
+    }
+
+    pub(crate) fn recreate_pipeline(&mut self, device_container: &DeviceContainer) {
+        self.alpha_pipeline =
+            Self::create_pipeline(device_container, &self.vs, &self.fs, BlendMode::Alpha);
+        self.additive_pipeline =
+            Self::create_pipeline(device_container, &self.vs, &self.fs, BlendMode::Additive);
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        device_container: &mut DeviceContainer,
+        instances: Vec<ParticleInstance>,
+        push_constants: particle_fs::ty::Constants,
+        set: Arc<PersistentDescriptorSet>,
+        blend: BlendMode,
+    ) {
+        let instance_count = instances.len() as u32;
+        let instance_buffer = CpuAccessibleBuffer::from_iter(
+            device_container.memory_allocator().as_ref(),
+            BufferUsage {
+                vertex_buffer: true,
+                ..Default::default()
+            },
+            false,
+            instances,
+        )
+        .unwrap();
+
+        let pipeline = match blend {
+            BlendMode::Alpha => self.alpha_pipeline.clone(),
+            BlendMode::Additive => self.additive_pipeline.clone(),
+        };
+
+        let mut builder = device_container.get_command_buffer_builder();
+
+        builder
+            .bind_pipeline_graphics(pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .bind_vertex_buffers(0, (self.quad_vertices.clone(), instance_buffer))
+            .bind_index_buffer(self.quad_indices.clone())
+            .push_constants(pipeline.layout().clone(), 0, push_constants)
+            .draw_indexed(self.quad_indices.len() as u32, instance_count, 0, 0, 0)
+            .unwrap();
+    }
+}
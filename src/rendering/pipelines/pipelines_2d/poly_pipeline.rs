@@ -12,15 +12,18 @@ use vulkano::{
             vertex_input::BuffersDefinition,
             viewport::{Viewport, ViewportState},
         },
-        GraphicsPipeline, Pipeline,
+        GraphicsPipeline, Pipeline, PipelineBindPoint,
     },
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
     shader::ShaderModule,
 };
 
-use crate::rendering::{
-    render_containers::device_container::DeviceContainer,
-    render_objects::shared::{BufferContainer2D, Vertex2D},
+use crate::{
+    public::objects::fill::Fill,
+    rendering::{
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{BufferContainer2DEdge, FillSets, Vertex2DEdge},
+    },
 };
 
 pub(crate) mod poly_vs {
@@ -39,6 +42,7 @@ pub(crate) mod poly_fs {
     vulkano_shaders::shader! {
         ty: "fragment",
         path: "src/shaders/shaders_2d/poly_pipeline.frag",
+        include: ["src/shaders/shaders_2d"],
         types_meta: {
             use bytemuck::{Pod, Zeroable};
 
@@ -51,6 +55,7 @@ pub(crate) struct PolyPipeline {
     vs: Arc<ShaderModule>,
     fs: Arc<ShaderModule>,
     pipeline: Arc<GraphicsPipeline>,
+    fill_sets: FillSets,
 }
 
 impl PolyPipeline {
@@ -59,8 +64,14 @@ impl PolyPipeline {
         let fs = poly_fs::load(device_container.device().clone()).unwrap();
 
         let pipeline = Self::create_pipeline(device_container, &vs, &fs);
+        let fill_sets = Self::create_fill_sets(device_container, &pipeline);
 
-        Self { vs, fs, pipeline }
+        Self {
+            vs,
+            fs,
+            pipeline,
+            fill_sets,
+        }
     }
 
     fn create_pipeline(
@@ -72,7 +83,7 @@ impl PolyPipeline {
             .color_blend_state(ColorBlendState::blend_alpha(ColorBlendState::new(1)))
             .render_pass(Subpass::from(device_container.render_pass().clone(), 0).unwrap())
             .input_assembly_state(InputAssemblyState::new())
-            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2D>())
+            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2DEdge>())
             .vertex_shader(vs.entry_point("main").unwrap(), ())
             .viewport_state(ViewportState::viewport_fixed_scissor_irrelevant([
                 Viewport {
@@ -86,20 +97,44 @@ impl PolyPipeline {
             .unwrap()
     }
 
+    fn create_fill_sets(
+        device_container: &DeviceContainer,
+        pipeline: &Arc<GraphicsPipeline>,
+    ) -> FillSets {
+        FillSets::new(
+            device_container,
+            pipeline.layout().set_layouts().get(0).unwrap(),
+        )
+    }
+
     pub(crate) fn recreate_pipeline(&mut self, device_container: &DeviceContainer) {
-        self.pipeline = Self::create_pipeline(device_container, &self.vs, &self.fs)
+        self.pipeline = Self::create_pipeline(device_container, &self.vs, &self.fs);
+        self.fill_sets = Self::create_fill_sets(device_container, &self.pipeline);
     }
 
     pub(crate) fn draw(
         &mut self,
         device_container: &mut DeviceContainer,
-        buffers: &BufferContainer2D,
+        buffers: &BufferContainer2DEdge,
         push_constants: poly_fs::ty::Constants,
+        fill: Option<&Fill>,
     ) {
+        let fill_set = self.fill_sets.get(
+            device_container,
+            self.pipeline.layout().set_layouts().get(0).unwrap(),
+            fill,
+        );
+
         let mut builder = device_container.get_command_buffer_builder();
 
         builder
             .bind_pipeline_graphics(self.pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                fill_set,
+            )
             .bind_vertex_buffers(0, buffers.vertex_buffer.clone())
             .bind_index_buffer(buffers.index_buffer.clone())
             .push_constants(self.pipeline.layout().clone(), 0, push_constants)
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+use vulkano::{
+    buffer::TypedBufferAccess,
+    command_buffer::{
+        AutoCommandBufferBuilder, CommandBufferUsage, RenderPassBeginInfo, SubpassContents,
+    },
+    image::view::ImageView,
+    pipeline::{
+        graphics::{
+            color_blend::ColorBlendState,
+            input_assembly::InputAssemblyState,
+            vertex_input::BuffersDefinition,
+            viewport::{Viewport, ViewportState},
+        },
+        GraphicsPipeline, Pipeline, PipelineBindPoint,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
+    shader::ShaderModule,
+};
+
+use crate::{
+    public::objects::fill::Fill,
+    rendering::{
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{BufferContainer2D, FillSets, Vertex2D},
+    },
+};
+
+pub(crate) mod rect_vs {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        path: "src/shaders/shaders_2d/rect_pipeline.vert",
+        types_meta: {
+            use bytemuck::{Pod, Zeroable};
+
+            #[derive(Clone, Copy, Zeroable, Pod)]
+        }
+    }
+}
+
+pub(crate) mod rect_fs {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        path: "src/shaders/shaders_2d/rect_pipeline.frag",
+        include: ["src/shaders/shaders_2d"],
+        types_meta: {
+            use bytemuck::{Pod, Zeroable};
+
+            #[derive(Clone, Copy, Zeroable, Pod)]
+        }
+    }
+}
+
+pub(crate) struct RectPipeline {
+    vs: Arc<ShaderModule>,
+    fs: Arc<ShaderModule>,
+    pipeline: Arc<GraphicsPipeline>,
+    fill_sets: FillSets,
+}
+
+impl RectPipeline {
+    pub(crate) fn new(device_container: &DeviceContainer) -> Self {
+        let vs = rect_vs::load(device_container.device().clone()).unwrap();
+        let fs = rect_fs::load(device_container.device().clone()).unwrap();
+
+        let pipeline = Self::create_pipeline(device_container, &vs, &fs);
+        let fill_sets = Self::create_fill_sets(device_container, &pipeline);
+
+        Self {
+            vs,
+            fs,
+            pipeline,
+            fill_sets,
+        }
+    }
+
+    fn create_pipeline(
+        device_container: &DeviceContainer,
+        vs: &Arc<ShaderModule>,
+        fs: &Arc<ShaderModule>,
+    ) -> Arc<GraphicsPipeline> {
+        GraphicsPipeline::start()
+            .color_blend_state(ColorBlendState::blend_alpha(ColorBlendState::new(1)))
+            .render_pass(Subpass::from(device_container.render_pass().clone(), 0).unwrap())
+            .input_assembly_state(InputAssemblyState::new())
+            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2D>())
+            .vertex_shader(vs.entry_point("main").unwrap(), ())
+            .viewport_state(ViewportState::viewport_fixed_scissor_irrelevant([
+                Viewport {
+                    origin: [0.0, 0.0],
+                    dimensions: device_container.resolution_f32(),
+                    depth_range: 0.0..1.0,
+                },
+            ]))
+            .fragment_shader(fs.entry_point("main").unwrap(), ())
+            .build(device_container.device().clone())
+            .unwrap()
+    }
+
+    fn create_fill_sets(
+        device_container: &DeviceContainer,
+        pipeline: &Arc<GraphicsPipeline>,
+    ) -> FillSets {
+        FillSets::new(
+            device_container,
+            pipeline.layout().set_layouts().get(0).unwrap(),
+        )
+    }
+
+    pub(crate) fn recreate_pipeline(&mut self, device_container: &DeviceContainer) {
+        self.pipeline = Self::create_pipeline(device_container, &self.vs, &self.fs);
+        self.fill_sets = Self::create_fill_sets(device_container, &self.pipeline);
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        device_container: &mut DeviceContainer,
+        buffers: &BufferContainer2D,
+        push_constants: rect_fs::ty::Constants,
+        fill: Option<&Fill>,
+    ) {
+        let fill_set = self.fill_sets.get(
+            device_container,
+            self.pipeline.layout().set_layouts().get(0).unwrap(),
+            fill,
+        );
+
+        let mut builder = device_container.get_command_buffer_builder();
+
+        builder
+            .bind_pipeline_graphics(self.pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                fill_set,
+            )
+            .bind_vertex_buffers(0, buffers.vertex_buffer.clone())
+            .bind_index_buffer(buffers.index_buffer.clone())
+            .push_constants(self.pipeline.layout().clone(), 0, push_constants)
+            .draw_indexed(buffers.index_buffer.len() as u32, 1, 0, 0, 0)
+            .unwrap();
+    }
+}
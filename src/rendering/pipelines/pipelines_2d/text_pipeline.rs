@@ -1,15 +1,17 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
-use fontdue::{Font, FontSettings, Metrics};
+use nalgebra_glm::UVec2;
 use vulkano::{
-    buffer::TypedBufferAccess,
+    buffer::{BufferUsage, CpuAccessibleBuffer, TypedBufferAccess},
     command_buffer::{
-        AutoCommandBufferBuilder, CommandBufferUsage, PrimaryCommandBufferAbstract,
+        AutoCommandBufferBuilder, BufferImageCopy, CommandBufferUsage, CopyBufferToImageInfo,
         RenderPassBeginInfo, SubpassContents,
     },
     descriptor_set::{PersistentDescriptorSet, WriteDescriptorSet},
     format::Format,
-    image::{view::ImageView, ImageDimensions, ImmutableImage},
+    image::{
+        view::ImageView, ImageAccess, ImageCreateFlags, ImageDimensions, ImageUsage, StorageImage,
+    },
     pipeline::{
         graphics::{
             color_blend::ColorBlendState,
@@ -22,14 +24,14 @@ use vulkano::{
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
     sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
     shader::ShaderModule,
-    sync::GpuFuture,
 };
 
 use crate::{
-    public::objects::obj_2d::text::TextFont,
+    public::objects::obj_2d::font::{FontHandle, FontRegistry},
     rendering::{
+        glyph_atlas::{AtlasGlyph, GlyphAtlas, GlyphKey},
         render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d_uv, BufferContainer2DUv, Vertex2DUv},
+        render_objects::shared::{BufferContainer2DGlyph, Vertex2DGlyph},
     },
 };
 
@@ -61,10 +63,12 @@ pub(crate) struct TextPipeline {
     vs: Arc<ShaderModule>,
     fs: Arc<ShaderModule>,
     pipeline: Arc<GraphicsPipeline>,
-    font_sets: HashMap<char, (Option<Arc<PersistentDescriptorSet>>, Metrics)>,
+    atlas: GlyphAtlas,
+    // Created again when the atlas grows or is repacked, render objects keep the one they were
+    // created with, so the image their glyphs are in stays alive
+    atlas_image: Option<(Arc<StorageImage>, Arc<PersistentDescriptorSet>)>,
     font_image_sampler: Arc<Sampler>,
-    comic_sans_font: Font,
-    roboto_font: Font,
+    fonts: FontRegistry,
 }
 
 impl TextPipeline {
@@ -85,28 +89,14 @@ impl TextPipeline {
         )
         .unwrap();
 
-        let comic_sans_font = Font::from_bytes(
-            include_bytes!("../../../../assets/fonts/comic-sans-ms/comicz.ttf")
-                as &[u8],
-            FontSettings::default(),
-        )
-        .unwrap();
-
-        let roboto_font = Font::from_bytes(
-            include_bytes!("../../../../assets/fonts/Roboto/Roboto-Regular.ttf")
-                as &[u8],
-            FontSettings::default(),
-        )
-        .unwrap();
-
         Self {
             vs,
             fs,
             pipeline,
-            font_sets: HashMap::new(),
+            atlas: GlyphAtlas::new(),
+            atlas_image: None,
             font_image_sampler,
-            comic_sans_font,
-            roboto_font,
+            fonts: FontRegistry::new(),
         }
     }
 
@@ -119,7 +109,7 @@ impl TextPipeline {
             .color_blend_state(ColorBlendState::blend_alpha(ColorBlendState::new(1)))
             .input_assembly_state(InputAssemblyState::new())
             .render_pass(Subpass::from(device_container.render_pass().clone(), 0).unwrap())
-            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2DUv>())
+            .vertex_input_state(BuffersDefinition::new().vertex::<Vertex2DGlyph>())
             .vertex_shader(vs.entry_point("main").unwrap(), ())
             .viewport_state(ViewportState::viewport_fixed_scissor_irrelevant([
                 Viewport {
@@ -137,91 +127,184 @@ impl TextPipeline {
         self.pipeline = Self::create_pipeline(device_container, &self.vs, &self.fs);
     }
 
-    pub(crate) fn get_or_create_set(
+    // Packs the glyphs of the text into the atlas and uploads the new ones. Glyphs are in the
+    // same order as the characters, the ones that didn't fit in the atlas have no position.
+    pub(crate) fn get_or_create_glyphs(
         &mut self,
         device_container: &mut DeviceContainer,
-        c: char,
-        font: &TextFont,
-    ) -> (Option<Arc<PersistentDescriptorSet>>, Metrics) {
-        if let Some(font_set) = self.font_sets.get(&c) {
-            return font_set.clone();
-        }
+        text: &str,
+        font: Option<FontHandle>,
+        size: f32,
+    ) -> Option<(Arc<PersistentDescriptorSet>, Vec<AtlasGlyph>)> {
+        // Nothing is drawn without any loaded fonts
+        let (font, _) = self.fonts.get(font)?;
+        let size = size.max(0.);
 
-        let actual_font;
-        match font {
-            TextFont::ComicSans => {
-                actual_font = &self.comic_sans_font;
-            }
-            TextFont::Roboto => {
-                actual_font = &self.roboto_font;
+        let generation = self.atlas.generation();
+        let mut glyphs = self.get_or_create_glyphs_in_atlas(text, font, size);
+        // Repacking moves the glyphs that were added before it and drops some of them. Looking
+        // them up again doesn't change the atlas, so all positions match the current image and
+        // the dropped glyphs aren't drawn.
+        if self.atlas.generation() != generation {
+            for (glyph, c) in glyphs.iter_mut().zip(text.chars()) {
+                let key = glyph_key(font, size, c);
+                *glyph = self.atlas.get(&key).unwrap_or(AtlasGlyph {
+                    position: None,
+                    ..*glyph
+                });
             }
         }
 
-        let (metrics, bitmap) = actual_font.rasterize(c, 17.);
+        self.upload_atlas(device_container);
+        let (_, set) = self.atlas_image.as_ref()?;
+        Some((set.clone(), glyphs))
+    }
 
-        if metrics.width == 0 {
-            self.font_sets.insert(c, (None, metrics.clone()));
-            return (None, metrics);
+    fn get_or_create_glyphs_in_atlas(
+        &mut self,
+        text: &str,
+        font: FontHandle,
+        size: f32,
+    ) -> Vec<AtlasGlyph> {
+        let mut glyphs = Vec::new();
+        for c in text.chars() {
+            let key = glyph_key(font, size, c);
+            let glyph = match self.atlas.get(&key) {
+                Some(glyph) => glyph,
+                None => {
+                    let (_, actual_font) = self.fonts.get(Some(font)).unwrap();
+                    let (metrics, bitmap) = actual_font.rasterize(c, size);
+                    self.atlas
+                        .insert(key, metrics, &bitmap)
+                        .unwrap_or(AtlasGlyph {
+                            metrics,
+                            position: None,
+                        })
+                }
+            };
+            glyphs.push(glyph);
         }
+        glyphs
+    }
 
-        let memory_allocator = device_container.memory_allocator();
-        let builder = device_container.get_command_buffer_builder();
+    // New glyphs are copied into the atlas image, which is created again when the atlas changed
+    // size or was repacked
+    fn upload_atlas(&mut self, device_container: &mut DeviceContainer) {
+        let (resized, dirty_regions) = self.atlas.take_changes();
+        if let (Some((image, _)), false) = (&self.atlas_image, resized) {
+            for (position, size) in dirty_regions {
+                upload_region(device_container, image, &self.atlas, position, size);
+            }
+            return;
+        }
 
-        let char_image_view = ImageView::new_default(
-            ImmutableImage::from_iter(
-                memory_allocator.as_ref(),
-                bitmap,
-                ImageDimensions::Dim2d {
-                    width: metrics.width as u32,
-                    height: metrics.height as u32,
-                    array_layers: 1,
-                },
-                1.into(),
-                Format::R8_UINT,
-                builder,
-            )
-            .unwrap(),
+        let size = self.atlas.size();
+        let image = StorageImage::with_usage(
+            device_container.memory_allocator().as_ref(),
+            ImageDimensions::Dim2d {
+                width: size,
+                height: size,
+                array_layers: 1,
+            },
+            Format::R8_UINT,
+            ImageUsage {
+                sampled: true,
+                transfer_dst: true,
+                ..Default::default()
+            },
+            ImageCreateFlags::empty(),
+            [device_container.queue_family_index()],
         )
         .unwrap();
+        upload_region(
+            device_container,
+            &image,
+            &self.atlas,
+            UVec2::new(0, 0),
+            UVec2::new(size, size),
+        );
 
         let set = PersistentDescriptorSet::new(
             device_container.descriptor_set_allocator().as_ref(),
             self.pipeline.layout().set_layouts().get(0).unwrap().clone(),
             [WriteDescriptorSet::image_view_sampler(
                 0,
-                char_image_view,
+                ImageView::new_default(image.clone()).unwrap(),
                 self.font_image_sampler.clone(),
             )],
         )
         .unwrap();
+        self.atlas_image = Some((image, set));
+    }
 
-        let res = (Some(set), metrics);
-        self.font_sets.insert(c, res.clone());
-        res
+    pub(crate) fn fonts_mut(&mut self) -> &mut FontRegistry {
+        &mut self.fonts
     }
 
     pub(crate) fn draw(
         &mut self,
         device_container: &mut DeviceContainer,
         push_constants: text_fs::ty::Constants,
-        sets_and_buffers: Vec<(Arc<PersistentDescriptorSet>, BufferContainer2DUv)>,
+        set: Arc<PersistentDescriptorSet>,
+        buffers: &BufferContainer2DGlyph,
     ) {
-        for (set, buffers) in sets_and_buffers {
-            let builder = device_container.get_command_buffer_builder();
-
-            builder
-                .bind_pipeline_graphics(self.pipeline.clone())
-                .push_constants(self.pipeline.layout().clone(), 0, push_constants)
-                .bind_descriptor_sets(
-                    PipelineBindPoint::Graphics,
-                    self.pipeline.layout().clone(),
-                    0,
-                    set,
-                )
-                .bind_vertex_buffers(0, buffers.vertex_buffer.clone())
-                .bind_index_buffer(buffers.index_buffer.clone())
-                .draw_indexed(buffers.index_buffer.len() as u32, 1, 0, 0, 0)
-                .unwrap();
-        }
+        let builder = device_container.get_command_buffer_builder();
+
+        builder
+            .bind_pipeline_graphics(self.pipeline.clone())
+            .push_constants(self.pipeline.layout().clone(), 0, push_constants)
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .bind_vertex_buffers(0, buffers.vertex_buffer.clone())
+            .bind_index_buffer(buffers.index_buffer.clone())
+            .draw_indexed(buffers.index_buffer.len() as u32, 1, 0, 0, 0)
+            .unwrap();
     }
 }
+
+fn glyph_key(font: FontHandle, size: f32, c: char) -> GlyphKey {
+    GlyphKey {
+        font,
+        size: size.to_bits(),
+        c,
+    }
+}
+
+// Copies a region of the atlas into the same region of the gpu image
+fn upload_region(
+    device_container: &mut DeviceContainer,
+    gpu_image: &Arc<StorageImage>,
+    atlas: &GlyphAtlas,
+    position: UVec2,
+    size: UVec2,
+) {
+    let staging_buffer = CpuAccessibleBuffer::from_iter(
+        device_container.memory_allocator().as_ref(),
+        BufferUsage {
+            transfer_src: true,
+            ..Default::default()
+        },
+        false,
+        atlas.region(position, size),
+    )
+    .unwrap();
+
+    let region = BufferImageCopy {
+        image_subresource: gpu_image.subresource_layers(),
+        image_offset: [position.x, position.y, 0],
+        image_extent: [size.x, size.y, 1],
+        ..Default::default()
+    };
+
+    device_container
+        .get_command_buffer_builder()
+        .copy_buffer_to_image(CopyBufferToImageInfo {
+            regions: [region].into(),
+            ..CopyBufferToImageInfo::buffer_image(staging_buffer, gpu_image.clone())
+        })
+        .unwrap();
+}
@@ -55,6 +55,9 @@ pub(crate) struct DeviceContainer {
     memory_allocator: Rc<GenericMemoryAllocator<Arc<FreeListAllocator>>>,
     command_buffer_allocator: Rc<StandardCommandBufferAllocator>,
     descriptor_set_allocator: Rc<StandardDescriptorSetAllocator>,
+
+    // Smooth edges for 2D shapes, turned off for pixel art
+    antialiasing: bool,
 }
 
 impl DeviceContainer {
@@ -269,6 +272,7 @@ impl DeviceContainer {
             memory_allocator,
             command_buffer_allocator,
             descriptor_set_allocator,
+            antialiasing: true,
         }
     }
 
@@ -497,4 +501,12 @@ impl DeviceContainer {
     pub(crate) fn resolution_f32(&self) -> [f32; 2] {
         self.resolution().map(|num| num as f32)
     }
+
+    pub(crate) fn antialiasing(&self) -> bool {
+        self.antialiasing
+    }
+
+    pub(crate) fn set_antialiasing(&mut self, antialiasing: bool) {
+        self.antialiasing = antialiasing;
+    }
 }
@@ -6,12 +6,16 @@ use crate::{
         pipelines::{
             pipelines_2d::{
                 circle_pipeline::CirclePipeline, figure_pipeline::FigurePipeline,
-                line_pipeline::LinePipeline, poly_pipeline::PolyPipeline,
-                text_pipeline::TextPipeline,
+                line_pipeline::LinePipeline, particle_pipeline::ParticlePipeline,
+                poly_pipeline::PolyPipeline, rect_pipeline::RectPipeline,
+                shadow_pipeline::ShadowPipeline, text_pipeline::TextPipeline,
             },
             pipelines_3d::block_pipeline::BlockPipeline,
         },
-        render_objects::{background_ro::BackgroundRenderObject, RenderObject2D, RenderObject3D},
+        render_objects::{
+            background_ro::BackgroundRenderObject, ro_2d::figure_ro::FigureRenderObject,
+            RenderObject2D, RenderObject3D,
+        },
     },
 };
 
@@ -19,22 +23,28 @@ use super::device_container::DeviceContainer;
 
 pub(super) struct PipelineContainer {
     poly_pipeline: PolyPipeline,
+    rect_pipeline: RectPipeline,
     circle_pipeline: CirclePipeline,
     line_pipeline: LinePipeline,
+    shadow_pipeline: ShadowPipeline,
     block_pipeline: BlockPipeline,
     pub text_pipeline: TextPipeline,
-    figure_pipeline: FigurePipeline,
+    pub figure_pipeline: FigurePipeline,
+    pub particle_pipeline: ParticlePipeline,
 }
 
 impl PipelineContainer {
     pub(super) fn new(device_container: &DeviceContainer) -> Self {
         Self {
             poly_pipeline: PolyPipeline::new(device_container),
+            rect_pipeline: RectPipeline::new(device_container),
             circle_pipeline: CirclePipeline::new(device_container),
             line_pipeline: LinePipeline::new(device_container),
+            shadow_pipeline: ShadowPipeline::new(device_container),
             block_pipeline: BlockPipeline::new(device_container),
             text_pipeline: TextPipeline::new(device_container),
             figure_pipeline: FigurePipeline::new(device_container),
+            particle_pipeline: ParticlePipeline::new(device_container),
         }
     }
 
@@ -43,9 +53,28 @@ impl PipelineContainer {
         self.circle_pipeline.recreate_pipeline(device_container);
         self.text_pipeline.recreate_pipeline(device_container);
         self.poly_pipeline.recreate_pipeline(device_container);
+        self.rect_pipeline.recreate_pipeline(device_container);
         self.block_pipeline.recreate_pipeline(device_container);
         self.figure_pipeline.recreate_pipeline(device_container);
         self.line_pipeline.recreate_pipeline(device_container);
+        self.particle_pipeline.recreate_pipeline(device_container);
+        self.shadow_pipeline.recreate_pipeline(device_container);
+    }
+
+    pub(super) fn free_unused_resources(&mut self) {
+        self.figure_pipeline.free_unused_images();
+    }
+
+    pub(super) fn render_background(
+        &mut self,
+        device_container: &mut DeviceContainer,
+        background: &mut BackgroundRenderObject,
+    ) {
+        background.draw(
+            &mut self.rect_pipeline,
+            &mut self.line_pipeline,
+            device_container,
+        );
     }
 
     pub(super) fn render_3d(
@@ -72,24 +101,51 @@ impl PipelineContainer {
     ) {
         while let Ok(object) = render_objects.remove() {
             match object {
-                RenderObject2D::RectObject(mut rect) => {
-                    rect.draw(&mut self.poly_pipeline, device_container, Some(camera_2d))
-                }
-                RenderObject2D::CircleObject(mut circle) => {
-                    circle.draw(&mut self.circle_pipeline, device_container, Some(camera_2d))
-                }
+                RenderObject2D::RectObject(mut rect) => rect.draw(
+                    &mut self.rect_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    Some(camera_2d),
+                ),
+                RenderObject2D::CircleObject(mut circle) => circle.draw(
+                    &mut self.circle_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    Some(camera_2d),
+                ),
                 RenderObject2D::LineObject(mut line) => {
-                    line.draw(&mut self.line_pipeline, device_container)
-                }
-                RenderObject2D::PolyObject(mut polygon) => {
-                    polygon.draw(&mut self.poly_pipeline, device_container, Some(camera_2d))
+                    line.draw(&mut self.line_pipeline, device_container, Some(camera_2d))
                 }
+                RenderObject2D::PolyObject(mut polygon) => polygon.draw(
+                    &mut self.poly_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    Some(camera_2d),
+                ),
+                RenderObject2D::PathObject(mut path) => path.draw(
+                    &mut self.poly_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    Some(camera_2d),
+                ),
                 RenderObject2D::TextObject(mut text) => {
                     text.draw(&mut self.text_pipeline, device_container, Some(camera_2d))
                 }
                 RenderObject2D::FigureObject(mut figure) => {
+                    batch_figures(&mut figure, render_objects);
                     figure.draw(&mut self.figure_pipeline, device_container, Some(camera_2d))
                 }
+                RenderObject2D::ParticleObject(mut particles) => particles.draw(
+                    &mut self.particle_pipeline,
+                    device_container,
+                    Some(camera_2d),
+                ),
+                RenderObject2D::ShadowObject(mut shadow) => {
+                    shadow.draw(&mut self.shadow_pipeline, device_container, Some(camera_2d))
+                }
+                RenderObject2D::TilemapObject(mut tilemap) => {
+                    tilemap.draw(&mut self.figure_pipeline, device_container, Some(camera_2d))
+                }
                 _ => (),
             }
         }
@@ -102,26 +158,62 @@ impl PipelineContainer {
     ) {
         while let Ok(object) = render_objects.remove() {
             match object {
-                RenderObject2D::RectObject(mut rect) => {
-                    rect.draw(&mut self.poly_pipeline, device_container, None)
-                }
-                RenderObject2D::CircleObject(mut circle) => {
-                    circle.draw(&mut self.circle_pipeline, device_container, None)
-                }
+                RenderObject2D::RectObject(mut rect) => rect.draw(
+                    &mut self.rect_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    None,
+                ),
+                RenderObject2D::CircleObject(mut circle) => circle.draw(
+                    &mut self.circle_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    None,
+                ),
                 RenderObject2D::LineObject(mut line) => {
-                    line.draw(&mut self.line_pipeline, device_container)
-                }
-                RenderObject2D::PolyObject(mut polygon) => {
-                    polygon.draw(&mut self.poly_pipeline, device_container, None)
+                    line.draw(&mut self.line_pipeline, device_container, None)
                 }
+                RenderObject2D::PolyObject(mut polygon) => polygon.draw(
+                    &mut self.poly_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    None,
+                ),
+                RenderObject2D::PathObject(mut path) => path.draw(
+                    &mut self.poly_pipeline,
+                    &mut self.line_pipeline,
+                    device_container,
+                    None,
+                ),
                 RenderObject2D::TextObject(mut text) => {
                     text.draw(&mut self.text_pipeline, device_container, None)
                 }
                 RenderObject2D::FigureObject(mut figure) => {
+                    batch_figures(&mut figure, render_objects);
                     figure.draw(&mut self.figure_pipeline, device_container, None)
                 }
+                RenderObject2D::ParticleObject(mut particles) => {
+                    particles.draw(&mut self.particle_pipeline, device_container, None)
+                }
+                RenderObject2D::ShadowObject(mut shadow) => {
+                    shadow.draw(&mut self.shadow_pipeline, device_container, None)
+                }
+                RenderObject2D::TilemapObject(mut tilemap) => {
+                    tilemap.draw(&mut self.figure_pipeline, device_container, None)
+                }
                 _ => (),
             }
         }
     }
 }
+
+// Merges the figures queued right after figure that use the same image into it,
+// so they only take one draw. Figures further away are not merged to keep the drawing order.
+fn batch_figures(figure: &mut FigureRenderObject, render_objects: &mut Queue<RenderObject2D>) {
+    while let Ok(RenderObject2D::FigureObject(next)) = render_objects.peek() {
+        if !figure.try_batch(&next) {
+            break;
+        }
+        render_objects.remove().unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::{Vec2, Vec3};
 use queues::{IsQueue, Queue};
 
 use crate::{
@@ -6,15 +6,30 @@ use crate::{
     public::objects::{
         background::Background,
         camera::{camera_2d::Camera2D, camera_3d::Camera3D},
-        obj_2d::{circle::Circle, line::Line, polygon::Polygon, rect::Rect, text::Text},
+        obj_2d::{
+            arc::Arc, circle::Circle, ellipse::Ellipse, font::FontRegistry, line::Line,
+            nine_slice::NineSlice, particles::ParticleEmitter, path::Path, pie::Pie,
+            points::Points, polygon::Polygon, polyline::Polyline, rect::Rect, ring::Ring,
+            text::Text, tilemap::Tilemap,
+        },
         obj_3d::block::Block,
+        transform_2d::Transform2D,
     },
     rendering::render_objects::{
         background_ro::BackgroundRenderObject,
         ro_2d::{
-            circle_ro::CircleRenderObject, figure_ro::FigureRenderObject,
-            line_ro::LineRenderObject, polygon_ro::PolygonRenderObject, rect_ro::RectRenderObject,
+            circle_ro::CircleRenderObject,
+            figure_ro::FigureRenderObject,
+            line_ro::LineRenderObject,
+            nine_slice_ro::nine_slice_render_object,
+            particle_ro::ParticleRenderObject,
+            path_ro::PathRenderObject,
+            points_ro::points_render_object,
+            polygon_ro::PolygonRenderObject,
+            rect_ro::RectRenderObject,
+            shadow_ro::{shadow_render_objects, ShadowShape},
             text_ro::TextRenderObject,
+            tilemap_ro::TilemapRenderObject,
         },
         ro_3d::block_ro::BlockRenderObject,
         RenderObject2D, RenderObject3D,
@@ -44,130 +59,426 @@ impl RenderContainer {
         T: Into<String>,
     {
         let event_loop_container = EventLoopContainer::new();
-        let device_container =
-            DeviceContainer::new(&event_loop_container.event_loop, width, height, window_title);
+        let mut device_container = DeviceContainer::new(
+            &event_loop_container.event_loop,
+            width,
+            height,
+            window_title,
+        );
 
         let pipeline_container = PipelineContainer::new(&device_container);
 
+        let background = BackgroundRenderObject::new(
+            Background::new_with_color(Vec3::new(0., 0., 0.)),
+            &mut device_container,
+        );
+
         Self {
             event_loop_container: Some(event_loop_container),
             device_container,
             pipeline_container,
 
-            background: BackgroundRenderObject::new(Background::new_with_color(Vec3::new(
-                0., 0., 0.,
-            ))),
+            background,
             render_objects_2d: Queue::new(),
             render_objects_ui: Queue::new(),
             render_objects_3d: Queue::new(),
         }
     }
 
-    pub(crate) fn circle(&mut self, circle: Circle) {
+    pub(crate) fn circle(&mut self, circle: Circle, transform: Transform2D) {
+        for shadow in shadow_render_objects(
+            ShadowShape::from_circle(&circle),
+            circle.shadow.as_ref(),
+            circle.glow.as_ref(),
+            transform,
+            &mut self.device_container,
+        ) {
+            self.render_objects_2d
+                .add(RenderObject2D::ShadowObject(shadow))
+                .unwrap();
+        }
+
         self.render_objects_2d
             .add(RenderObject2D::CircleObject(CircleRenderObject::new(
                 circle,
+                transform,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn ui_circle(&mut self, circle: Circle) {
+    pub(crate) fn ui_circle(&mut self, circle: Circle, transform: Transform2D) {
+        for shadow in shadow_render_objects(
+            ShadowShape::from_circle(&circle),
+            circle.shadow.as_ref(),
+            circle.glow.as_ref(),
+            transform,
+            &mut self.device_container,
+        ) {
+            self.render_objects_ui
+                .add(RenderObject2D::ShadowObject(shadow))
+                .unwrap();
+        }
+
         self.render_objects_ui
             .add(RenderObject2D::CircleObject(CircleRenderObject::new(
                 circle,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ellipse(&mut self, ellipse: Ellipse, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                ellipse,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_ellipse(&mut self, ellipse: Ellipse, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                ellipse,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn arc(&mut self, arc: Arc, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                arc,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_arc(&mut self, arc: Arc, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                arc,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ring(&mut self, ring: Ring, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                ring,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_ring(&mut self, ring: Ring, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                ring,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn pie(&mut self, pie: Pie, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                pie,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_pie(&mut self, pie: Pie, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::CircleObject(CircleRenderObject::new(
+                pie,
+                transform,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn rect(&mut self, rect: Rect) {
+    pub(crate) fn rect(&mut self, rect: Rect, transform: Transform2D) {
+        for shadow in shadow_render_objects(
+            ShadowShape::from_rect(&rect),
+            rect.shadow.as_ref(),
+            rect.glow.as_ref(),
+            transform,
+            &mut self.device_container,
+        ) {
+            self.render_objects_2d
+                .add(RenderObject2D::ShadowObject(shadow))
+                .unwrap();
+        }
+
         self.render_objects_2d
             .add(RenderObject2D::RectObject(RectRenderObject::new(
                 rect,
+                transform,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn ui_rect(&mut self, rect: Rect) {
+    pub(crate) fn ui_rect(&mut self, rect: Rect, transform: Transform2D) {
+        for shadow in shadow_render_objects(
+            ShadowShape::from_rect(&rect),
+            rect.shadow.as_ref(),
+            rect.glow.as_ref(),
+            transform,
+            &mut self.device_container,
+        ) {
+            self.render_objects_ui
+                .add(RenderObject2D::ShadowObject(shadow))
+                .unwrap();
+        }
+
         self.render_objects_ui
             .add(RenderObject2D::RectObject(RectRenderObject::new(
                 rect,
+                transform,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn polygon(&mut self, polygon: Polygon) {
+    pub(crate) fn polygon(&mut self, polygon: Polygon, transform: Transform2D, camera_zoom: f32) {
         self.render_objects_2d
             .add(RenderObject2D::PolyObject(PolygonRenderObject::new(
                 polygon,
+                transform,
+                camera_zoom,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn ui_polygon(&mut self, polygon: Polygon) {
+    pub(crate) fn ui_polygon(&mut self, polygon: Polygon, transform: Transform2D) {
         self.render_objects_ui
             .add(RenderObject2D::PolyObject(PolygonRenderObject::new(
                 polygon,
+                transform,
+                1.,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn path(&mut self, path: Path, transform: Transform2D, camera_zoom: f32) {
+        self.render_objects_2d
+            .add(RenderObject2D::PathObject(PathRenderObject::new(
+                path,
+                transform,
+                camera_zoom,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_path(&mut self, path: Path, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::PathObject(PathRenderObject::new(
+                path,
+                transform,
+                1.,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn line(&mut self, line: Line) {
+    pub(crate) fn line(&mut self, line: Line, transform: Transform2D) {
         self.render_objects_2d
             .add(RenderObject2D::LineObject(LineRenderObject::new(
                 line,
+                transform,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn ui_line(&mut self, line: Line) {
+    pub(crate) fn ui_line(&mut self, line: Line, transform: Transform2D) {
         self.render_objects_ui
             .add(RenderObject2D::LineObject(LineRenderObject::new(
                 line,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn polyline(&mut self, polyline: Polyline, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::LineObject(LineRenderObject::new(
+                polyline,
+                transform,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_polyline(&mut self, polyline: Polyline, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::LineObject(LineRenderObject::new(
+                polyline,
+                transform,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn text(&mut self, text: Text) {
+    pub(crate) fn text(&mut self, text: Text, transform: Transform2D) {
         self.render_objects_2d
             .add(RenderObject2D::TextObject(TextRenderObject::new(
                 text,
+                transform,
                 &mut self.pipeline_container.text_pipeline,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn ui_text(&mut self, text: Text) {
+    pub(crate) fn ui_text(&mut self, text: Text, transform: Transform2D) {
         self.render_objects_ui
             .add(RenderObject2D::TextObject(TextRenderObject::new(
                 text,
+                transform,
                 &mut self.pipeline_container.text_pipeline,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn figure(&mut self, figure: Figure) {
+    pub(crate) fn figure(&mut self, figure: Figure, transform: Transform2D) {
         self.render_objects_2d
             .add(RenderObject2D::FigureObject(FigureRenderObject::new(
                 figure,
+                transform,
+                &mut self.pipeline_container.figure_pipeline,
                 &mut self.device_container,
             )))
             .unwrap();
     }
 
-    pub(crate) fn ui_figure(&mut self, figure: Figure) {
+    pub(crate) fn ui_figure(&mut self, figure: Figure, transform: Transform2D) {
         self.render_objects_ui
             .add(RenderObject2D::FigureObject(FigureRenderObject::new(
                 figure,
+                transform,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn nine_slice(&mut self, nine_slice: NineSlice, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::FigureObject(nine_slice_render_object(
+                nine_slice,
+                transform,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_nine_slice(&mut self, nine_slice: NineSlice, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::FigureObject(nine_slice_render_object(
+                nine_slice,
+                transform,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn points(&mut self, points: Points, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::ParticleObject(points_render_object(
+                points,
+                transform,
+                &self.pipeline_container.particle_pipeline,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_points(&mut self, points: Points, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::ParticleObject(points_render_object(
+                points,
+                transform,
+                &self.pipeline_container.particle_pipeline,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn particles(&mut self, emitter: &ParticleEmitter, transform: Transform2D) {
+        self.render_objects_2d
+            .add(RenderObject2D::ParticleObject(ParticleRenderObject::new(
+                emitter,
+                transform,
+                &self.pipeline_container.particle_pipeline,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_particles(&mut self, emitter: &ParticleEmitter, transform: Transform2D) {
+        self.render_objects_ui
+            .add(RenderObject2D::ParticleObject(ParticleRenderObject::new(
+                emitter,
+                transform,
+                &self.pipeline_container.particle_pipeline,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn tilemap(
+        &mut self,
+        tilemap: &mut Tilemap,
+        transform: Transform2D,
+        camera_2d: &Camera2D,
+    ) {
+        let resolution = self.resolution();
+        self.render_objects_2d
+            .add(RenderObject2D::TilemapObject(TilemapRenderObject::new(
+                tilemap,
+                transform,
+                camera_2d.view_transform() * transform,
+                resolution,
+                &mut self.pipeline_container.figure_pipeline,
+                &mut self.device_container,
+            )))
+            .unwrap();
+    }
+
+    pub(crate) fn ui_tilemap(&mut self, tilemap: &mut Tilemap, transform: Transform2D) {
+        let resolution = self.resolution();
+        self.render_objects_ui
+            .add(RenderObject2D::TilemapObject(TilemapRenderObject::new(
+                tilemap,
+                transform,
+                transform,
+                resolution,
+                &mut self.pipeline_container.figure_pipeline,
                 &mut self.device_container,
             )))
             .unwrap();
@@ -183,7 +494,24 @@ impl RenderContainer {
     }
 
     pub(crate) fn background(&mut self, background: Background) {
-        self.background = BackgroundRenderObject::new(background);
+        self.background = BackgroundRenderObject::new(background, &mut self.device_container);
+    }
+
+    pub(crate) fn set_antialiasing(&mut self, antialiasing: bool) {
+        self.device_container.set_antialiasing(antialiasing);
+    }
+
+    pub(crate) fn antialiasing(&self) -> bool {
+        self.device_container.antialiasing()
+    }
+
+    pub(crate) fn fonts_mut(&mut self) -> &mut FontRegistry {
+        self.pipeline_container.text_pipeline.fonts_mut()
+    }
+
+    pub(crate) fn resolution(&self) -> Vec2 {
+        let resolution = self.device_container.resolution_f32();
+        Vec2::new(resolution[0], resolution[1])
     }
 
     pub(crate) fn event_loop(&mut self) -> EventLoopContainer {
@@ -207,6 +535,9 @@ impl RenderContainer {
 
         self.device_container.begin_draw(&self.background);
 
+        self.pipeline_container
+            .render_background(&mut self.device_container, &mut self.background);
+
         self.pipeline_container.render_3d(
             &mut self.device_container,
             &mut self.render_objects_3d,
@@ -223,5 +554,7 @@ impl RenderContainer {
             .render_ui(&mut self.device_container, &mut self.render_objects_ui);
 
         self.device_container.end_draw();
+
+        self.pipeline_container.free_unused_resources();
     }
 }
@@ -1,15 +1,39 @@
+use nalgebra_glm::Vec2;
+
 use crate::{
-    public::objects::background::Background,
-    rendering::render_containers::device_container::DeviceContainer,
+    public::objects::{
+        background::Background,
+        obj_2d::{anchor::Anchor, rect::Rect},
+        transform_2d::Transform2D,
+    },
+    rendering::{
+        pipelines::pipelines_2d::{line_pipeline::LinePipeline, rect_pipeline::RectPipeline},
+        render_containers::device_container::DeviceContainer,
+    },
 };
 
+use super::ro_2d::rect_ro::RectRenderObject;
+
 pub(crate) struct BackgroundRenderObject {
     background: Background,
+    // Screen filling rect, only used when the background has a fill
+    rect: Option<RectRenderObject>,
 }
 
 impl BackgroundRenderObject {
-    pub(crate) fn new(background: Background) -> Self {
-        Self { background }
+    pub(crate) fn new(background: Background, device_container: &mut DeviceContainer) -> Self {
+        let rect = background.fill.clone().map(|fill| {
+            RectRenderObject::new(
+                Rect::new_default()
+                    .with_position(Vec2::new(0., 0.))
+                    .with_anchor(Anchor::TopLeft)
+                    .with_fill(fill),
+                Transform2D::identity(),
+                device_container,
+            )
+        });
+
+        Self { background, rect }
     }
 
     pub fn background_color(&self) -> [f32; 4] {
@@ -20,4 +44,18 @@ impl BackgroundRenderObject {
             1.,
         ]
     }
+
+    pub(crate) fn draw(
+        &mut self,
+        pipeline: &mut RectPipeline,
+        line_pipeline: &mut LinePipeline,
+        device_container: &mut DeviceContainer,
+    ) {
+        if let Some(rect) = &mut self.rect {
+            // The resolution can change between frames
+            let resolution = device_container.resolution_f32();
+            rect.set_size(Vec2::new(resolution[0], resolution[1]));
+            rect.draw(pipeline, line_pipeline, device_container, None);
+        }
+    }
 }
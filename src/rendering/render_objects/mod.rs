@@ -1,7 +1,9 @@
 use self::{
     ro_2d::{
-        circle_ro::CircleRenderObject, line_ro::LineRenderObject, polygon_ro::PolygonRenderObject,
-        rect_ro::RectRenderObject, text_ro::TextRenderObject, figure_ro::FigureRenderObject,
+        circle_ro::CircleRenderObject, figure_ro::FigureRenderObject, line_ro::LineRenderObject,
+        particle_ro::ParticleRenderObject, path_ro::PathRenderObject,
+        polygon_ro::PolygonRenderObject, rect_ro::RectRenderObject, shadow_ro::ShadowRenderObject,
+        text_ro::TextRenderObject, tilemap_ro::TilemapRenderObject,
     },
     ro_3d::block_ro::BlockRenderObject,
 };
@@ -9,7 +11,7 @@ use self::{
 pub(super) mod background_ro;
 pub(super) mod ro_2d;
 pub(super) mod ro_3d;
-pub(super) mod shared;
+pub(crate) mod shared;
 
 #[derive(Clone)]
 pub(super) enum RenderObject3D {
@@ -22,6 +24,10 @@ pub(super) enum RenderObject2D {
     CircleObject(CircleRenderObject),
     LineObject(LineRenderObject),
     PolyObject(PolygonRenderObject),
+    PathObject(PathRenderObject),
     TextObject(TextRenderObject),
     FigureObject(FigureRenderObject),
+    ParticleObject(ParticleRenderObject),
+    ShadowObject(ShadowRenderObject),
+    TilemapObject(TilemapRenderObject),
 }
@@ -1,20 +1,160 @@
+use std::f32::consts::TAU;
+
+use nalgebra_glm::{Vec2, Vec4};
+
 use crate::{
-    public::objects::obj_2d::circle::Circle,
+    public::objects::{
+        fill::Fill,
+        obj_2d::{arc::Arc, circle::Circle, ellipse::Ellipse, pie::Pie, ring::Ring},
+        transform_2d::Transform2D,
+        Border,
+    },
     rendering::{
-        pipelines::pipelines_2d::circle_pipeline::{circle_fs, CirclePipeline},
+        geometry::stroke::ellipse_outline,
+        pipelines::pipelines_2d::{
+            circle_pipeline::{circle_fs, CirclePipeline},
+            line_pipeline::LinePipeline,
+        },
         render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d, BufferContainer2D, Vertex2D},
-    }, Camera2D,
+        render_objects::shared::{
+            create_buffers_2d, fill_color, transform_constants, BufferContainer2D, Vertex2D,
+        },
+    },
+    Camera2D,
 };
 
+use super::line_ro::LineRenderObject;
+
+// All round shapes are drawn as (part of) an elliptical annulus by the circle pipeline
+#[derive(Clone)]
+pub(crate) struct CircleShape {
+    color: Vec4,
+    fill: Option<Fill>,
+    position: Vec2,
+    radii: Vec2,
+    inner_scale: f32,
+    start_angle: f32,
+    end_angle: f32,
+    border: Option<Border>,
+}
+
+impl From<Circle> for CircleShape {
+    fn from(circle: Circle) -> Self {
+        Self {
+            color: circle.color,
+            fill: circle.fill,
+            position: circle.position,
+            radii: Vec2::new(circle.radius, circle.radius),
+            inner_scale: 0.,
+            start_angle: 0.,
+            end_angle: TAU,
+            border: circle.border,
+        }
+    }
+}
+
+impl From<Ellipse> for CircleShape {
+    fn from(ellipse: Ellipse) -> Self {
+        Self {
+            color: ellipse.color,
+            fill: None,
+            position: ellipse.position,
+            radii: ellipse.radii,
+            inner_scale: 0.,
+            start_angle: 0.,
+            end_angle: TAU,
+            border: ellipse.border,
+        }
+    }
+}
+
+impl From<Ring> for CircleShape {
+    fn from(ring: Ring) -> Self {
+        Self {
+            color: ring.color,
+            fill: None,
+            position: ring.position,
+            radii: Vec2::new(ring.radius, ring.radius),
+            inner_scale: inner_scale(ring.inner_radius, ring.radius),
+            start_angle: 0.,
+            end_angle: TAU,
+            border: ring.border,
+        }
+    }
+}
+
+impl From<Arc> for CircleShape {
+    fn from(arc: Arc) -> Self {
+        let outer_radius = arc.radius + arc.thickness * 0.5;
+        let inner_radius = arc.radius - arc.thickness * 0.5;
+        let (start_angle, end_angle) = normalize_angles(arc.start_angle, arc.end_angle);
+        Self {
+            color: arc.color,
+            fill: None,
+            position: arc.position,
+            radii: Vec2::new(outer_radius, outer_radius),
+            inner_scale: inner_scale(inner_radius, outer_radius),
+            start_angle,
+            end_angle,
+            border: arc.border,
+        }
+    }
+}
+
+impl From<Pie> for CircleShape {
+    fn from(pie: Pie) -> Self {
+        let (start_angle, end_angle) = normalize_angles(pie.start_angle, pie.end_angle);
+        Self {
+            color: pie.color,
+            fill: None,
+            position: pie.position,
+            radii: Vec2::new(pie.radius, pie.radius),
+            inner_scale: 0.,
+            start_angle,
+            end_angle,
+            border: pie.border,
+        }
+    }
+}
+
+fn inner_scale(inner_radius: f32, outer_radius: f32) -> f32 {
+    if outer_radius <= 0. {
+        return 0.;
+    }
+    (inner_radius / outer_radius).clamp(0., 1.)
+}
+
+// Arcs always go from start_angle to end_angle in the positive direction, so a reversed range
+// wraps around instead of drawing the arc between the swapped angles. A sweep of a full turn or
+// more in either direction draws the whole circle.
+fn normalize_angles(start_angle: f32, end_angle: f32) -> (f32, f32) {
+    let sweep = end_angle - start_angle;
+    let sweep = if sweep.abs() >= TAU {
+        TAU
+    } else {
+        sweep.rem_euclid(TAU)
+    };
+    (start_angle, start_angle + sweep)
+}
+
 #[derive(Clone)]
 pub(crate) struct CircleRenderObject {
-    circle: Circle,
+    shape: CircleShape,
     buffers: BufferContainer2D,
+    // Dashed borders of full ellipses are drawn as lines on top of the shape
+    outline: Option<LineRenderObject>,
+    transform: Transform2D,
 }
 
 impl CircleRenderObject {
-    pub(crate) fn new(circle: Circle, device_container: &mut DeviceContainer) -> Self {
+    pub(crate) fn new<T>(
+        shape: T,
+        transform: Transform2D,
+        device_container: &mut DeviceContainer,
+    ) -> Self
+    where
+        T: Into<CircleShape>,
+    {
         static mut BUFFERS: Option<BufferContainer2D> = None;
         let buffers;
 
@@ -26,20 +166,53 @@ impl CircleRenderObject {
             }
             buffers = BUFFERS.as_ref().unwrap().clone();
         }
-        Self { circle, buffers }
+
+        let mut shape: CircleShape = shape.into();
+        let full_ellipse = shape.inner_scale == 0. && shape.end_angle - shape.start_angle >= TAU;
+        let outline = match &shape.border {
+            Some(border) if border.style.is_dashed() && full_ellipse => {
+                Some(LineRenderObject::new_with_mesh(
+                    ellipse_outline(shape.radii, border),
+                    transform.translate(shape.position),
+                    device_container,
+                ))
+            }
+            _ => None,
+        };
+        if outline.is_some() {
+            shape.border = None;
+        }
+
+        Self {
+            shape,
+            buffers,
+            outline,
+            transform,
+        }
     }
 
     pub(crate) fn draw(
         &mut self,
         pipeline: &mut CirclePipeline,
+        line_pipeline: &mut LinePipeline,
         device_container: &mut DeviceContainer,
         camera_2d: Option<&Camera2D>
     ) {
+        // The shader divides by the radii, and there is nothing to see anyway
+        if !(self.shape.radii.x > 0. && self.shape.radii.y > 0.) {
+            return;
+        }
+
         pipeline.draw(
             device_container,
             &self.buffers,
             self.create_push_constants(device_container, camera_2d),
+            self.shape.fill.as_ref(),
         );
+
+        if let Some(outline) = &mut self.outline {
+            outline.draw(line_pipeline, device_container, camera_2d);
+        }
     }
 
     #[allow(non_snake_case)]
@@ -48,24 +221,31 @@ impl CircleRenderObject {
         device_container: &DeviceContainer,
         camera_2d: Option<&Camera2D>
     ) -> circle_fs::ty::Constants {
-        let (borderColor, borderWidth) = match &self.circle.border {
-            Some(border) => (border.color.as_ref().clone(), border.width),
-            None => ([0.; 4], 0),
+        let (borderColor, borderWidth, borderOuter) = match &self.shape.border {
+            Some(border) => (
+                border.color.as_ref().clone(),
+                border.width as f32,
+                border.outer_extent(),
+            ),
+            None => ([0.; 4], 0., 0.),
         };
 
-        let cameraPos = match camera_2d {
-            Some(camera_2d) => camera_2d.position.as_ref().clone(),
-            None => [0.; 2],
-        };
+        let model = self.transform.translate(self.shape.position);
+        let (transform, translation) = transform_constants(camera_2d, &model);
 
         circle_fs::ty::Constants {
-            resolution: device_container.resolution(),
-            color: self.circle.color.as_ref().clone(),
-            position: self.circle.position.as_ref().clone(),
+            color: fill_color(self.shape.color, self.shape.fill.as_ref()),
             borderColor,
+            transform,
+            resolution: device_container.resolution(),
+            radii: self.shape.radii.as_ref().clone(),
+            translation,
+            innerScale: self.shape.inner_scale,
+            startAngle: self.shape.start_angle,
+            endAngle: self.shape.end_angle,
             borderWidth,
-            radius: self.circle.radius,
-            cameraPos,
+            borderOuter,
+            antialiasing: device_container.antialiasing() as u32,
         }
     }
 
@@ -1,34 +1,85 @@
+use std::sync::Arc;
+
+use nalgebra_glm::Vec2;
+use vulkano::descriptor_set::PersistentDescriptorSet;
+
 use crate::{
     rendering::{
         pipelines::pipelines_2d::figure_pipeline::{figure_fs, FigurePipeline},
         render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d, BufferContainer2D, Vertex2D, BufferContainer2DUv, Vertex2DUv, create_buffers_2d_uv},
+        render_objects::shared::{transform_constants, Vertex2DUvColor},
     },
-    Camera2D, Figure,
+    Camera2D, Figure, Transform2D,
 };
 
+// Corners of a figure relative to its size, in the order of QUAD_INDICES
+const QUAD_CORNERS: [[f32; 2]; 4] = [[-0.5, -0.5], [0.5, -0.5], [-0.5, 0.5], [0.5, 0.5]];
+pub(crate) const QUAD_INDICES: [u32; 6] = [0, 1, 2, 2, 1, 3];
+
+// Holds one or more figures using the same image and sampling, which are drawn together
 #[derive(Clone)]
 pub(crate) struct FigureRenderObject {
-    figure: Figure,
-    buffers: BufferContainer2DUv,
+    set: Option<Arc<PersistentDescriptorSet>>,
+    vertices: Vec<Vertex2DUvColor>,
 }
 
 impl FigureRenderObject {
-    pub(crate) fn new(figure: Figure, device_container: &mut DeviceContainer) -> Self {
-        static mut BUFFERS: Option<BufferContainer2DUv> = None;
-        let buffers;
-
-        // Unsafe is used to change these static values.
-        // This is definitely safe, even thought the compiler can't verify.
-        unsafe {
-            if let None = BUFFERS {
-                BUFFERS = Some(Self::create_buffers(device_container));
-            }
-
-            buffers = BUFFERS.as_ref().unwrap().clone();
+    pub(crate) fn new(
+        figure: Figure,
+        transform: Transform2D,
+        figure_pipeline: &mut FigurePipeline,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        let set = figure_pipeline.get_or_create_set(
+            device_container,
+            &figure.image,
+            figure.filter,
+            figure.wrap,
+            figure.mipmaps,
+        );
+
+        // The object transform is applied here so figures with different transforms
+        // can still be drawn together
+        let (uv_min, uv_max) = figure.uv_region();
+        let color = figure.color();
+        let vertices = QUAD_CORNERS
+            .iter()
+            .map(|corner| {
+                let corner = Vec2::new(corner[0], corner[1]);
+                let position = figure.position + corner.component_mul(&figure.size);
+                let t = corner.add_scalar(0.5);
+
+                Vertex2DUvColor {
+                    position: transform.transform_point(position).into(),
+                    uvCoord: [
+                        uv_min[0] + (uv_max[0] - uv_min[0]) * t.x,
+                        uv_min[1] + (uv_max[1] - uv_min[1]) * t.y,
+                    ],
+                    color,
+                }
+            })
+            .collect();
+
+        Self { set, vertices }
+    }
+
+    // Quads that were already built, four vertices each in the order of QUAD_CORNERS
+    pub(crate) fn new_with_vertices(
+        set: Option<Arc<PersistentDescriptorSet>>,
+        vertices: Vec<Vertex2DUvColor>,
+    ) -> Self {
+        Self { set, vertices }
+    }
+
+    // Adds the figures of other if they use the same image and sampling
+    pub(crate) fn try_batch(&mut self, other: &FigureRenderObject) -> bool {
+        match (&self.set, &other.set) {
+            (Some(set), Some(other_set)) if Arc::ptr_eq(set, other_set) => (),
+            _ => return false,
         }
 
-        Self { figure, buffers }
+        self.vertices.extend_from_slice(&other.vertices);
+        true
     }
 
     pub(crate) fn draw(
@@ -37,61 +88,35 @@ impl FigureRenderObject {
         device_container: &mut DeviceContainer,
         camera_2d: Option<&Camera2D>,
     ) {
+        let set = match &self.set {
+            Some(set) => set.clone(),
+            None => return,
+        };
+
+        let indices = (0..self.vertices.len() as u32 / 4)
+            .flat_map(|quad| QUAD_INDICES.iter().map(move |index| quad * 4 + index))
+            .collect();
+
         pipeline.draw(
             device_container,
-            &self.buffers,
+            self.vertices.clone(),
+            indices,
             self.create_push_constants(device_container, camera_2d),
+            set,
         );
     }
 
-    #[allow(non_snake_case)]
     fn create_push_constants(
         &self,
         device_container: &DeviceContainer,
         camera_2d: Option<&Camera2D>,
     ) -> figure_fs::ty::Constants {
-        let (borderColor, borderWidth) = match &self.figure.border {
-            Some(border) => (border.color.as_ref().clone(), border.width),
-            None => ([0.; 4], 0),
-        };
-
-        let cameraPos = match camera_2d {
-            Some(camera_2d) => camera_2d.position.as_ref().clone(),
-            None => [0.; 2],
-        };
+        let (transform, translation) = transform_constants(camera_2d, &Transform2D::identity());
 
         figure_fs::ty::Constants {
             resolution: device_container.resolution(),
-            position: self.figure.position.as_ref().clone(),
-            borderColor,
-            size: self.figure.size.as_ref().clone(),
-            borderWidth,
-            cameraPos,
+            translation,
+            transform,
         }
     }
-
-    fn create_buffers(device_container: &mut DeviceContainer) -> BufferContainer2DUv {
-        let vertices = vec![
-            Vertex2DUv {
-                position: [-0.5, -0.5],
-                uvCoord: [0., 0.],
-            },
-            Vertex2DUv {
-                position: [0.5, -0.5],
-                uvCoord: [1., 0.],
-            },
-            Vertex2DUv {
-                position: [-0.5, 0.5],
-                uvCoord: [0., 1.],
-            },
-            Vertex2DUv {
-                position: [0.5, 0.5],
-                uvCoord: [1., 1.],
-            },
-        ];
-
-        let indices = vec![0, 1, 2, 2, 1, 3];
-
-        create_buffers_2d_uv(device_container, vertices, indices)
-    }
 }
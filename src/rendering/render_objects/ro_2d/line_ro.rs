@@ -1,70 +1,93 @@
 use crate::{
-    public::objects::obj_2d::line::Line,
+    public::objects::{camera::camera_2d::Camera2D, transform_2d::Transform2D},
     rendering::{
-        render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d, BufferContainer2D, Vertex2D},
+        geometry::{stroke::Stroke, Mesh2D},
         pipelines::pipelines_2d::line_pipeline::{line_fs, LinePipeline},
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{
+            create_buffers_2d_color, transform_constants, BufferContainer2DColor, Vertex2DColor,
+        },
     },
 };
 
 #[derive(Clone)]
 pub(crate) struct LineRenderObject {
-    line: Line,
-    buffers: BufferContainer2D,
+    buffers: Option<BufferContainer2DColor>,
+    transform: Transform2D,
 }
 
 impl LineRenderObject {
-    pub(crate) fn new(line: Line, device_container: &mut DeviceContainer) -> Self {
-        static mut BUFFERS: Option<BufferContainer2D> = None;
-        let buffers;
+    pub(crate) fn new<T>(
+        stroke: T,
+        transform: Transform2D,
+        device_container: &mut DeviceContainer,
+    ) -> Self
+    where
+        T: Into<Stroke>,
+    {
+        Self::new_with_mesh(stroke.into().tessellate(), transform, device_container)
+    }
 
-        // Unsafe is used to change these static values.
-        // This is definitely safe, even thought the compiler can't verify.
-        unsafe {
-            if let None = BUFFERS {
-                BUFFERS = Some(Self::create_buffers(device_container));
-            }
-            buffers = BUFFERS.as_ref().unwrap().clone();
-        }
-        Self { line, buffers }
+    pub(crate) fn new_with_mesh(
+        mesh: Mesh2D,
+        transform: Transform2D,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        let buffers = Self::create_buffers(mesh, device_container);
+        Self { buffers, transform }
     }
 
     pub(crate) fn draw(
         &mut self,
         pipeline: &mut LinePipeline,
         device_container: &mut DeviceContainer,
+        camera_2d: Option<&Camera2D>,
     ) {
-        pipeline.draw(
-            device_container,
-            &self.buffers,
-            self.create_push_constants(),
-        );
+        if let Some(buffers) = &self.buffers {
+            pipeline.draw(
+                device_container,
+                buffers,
+                self.create_push_constants(device_container, camera_2d),
+            );
+        }
     }
 
-    pub(crate) fn create_push_constants(&self) -> line_fs::ty::Constants {
+    fn create_push_constants(
+        &self,
+        device_container: &DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) -> line_fs::ty::Constants {
+        let (transform, translation) = transform_constants(camera_2d, &self.transform);
+
         line_fs::ty::Constants {
-            color: self.line.color.as_ref().clone(),
+            resolution: device_container.resolution(),
+            translation,
+            transform,
         }
     }
 
-    fn create_buffers(device_container: &mut DeviceContainer) -> BufferContainer2D {
-        let vertices = vec![
-            Vertex2D {
-                position: [-0.5, -0.5],
-            },
-            Vertex2D {
-                position: [0.5, -0.5],
-            },
-            Vertex2D {
-                position: [-0.5, 0.5],
-            },
-            Vertex2D {
-                position: [0.5, 0.5],
-            },
-        ];
+    fn create_buffers(
+        mesh: Mesh2D,
+        device_container: &mut DeviceContainer,
+    ) -> Option<BufferContainer2DColor> {
+        if mesh.is_empty() {
+            return None;
+        }
 
-        let indices = vec![0, 1, 2, 2, 1, 3];
+        let vertices = mesh
+            .positions
+            .iter()
+            .zip(mesh.colors.iter())
+            .map(|(position, color)| Vertex2DColor {
+                position: position.as_ref().clone(),
+                color: color.as_ref().clone(),
+            })
+            .collect();
 
-        create_buffers_2d(device_container, vertices, indices)
+        Some(create_buffers_2d_color(
+            device_container,
+            vertices,
+            mesh.indices,
+        ))
     }
 }
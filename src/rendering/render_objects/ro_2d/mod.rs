@@ -1,6 +1,12 @@
 pub(crate) mod circle_ro;
 pub(crate) mod figure_ro;
 pub(crate) mod line_ro;
+pub(crate) mod nine_slice_ro;
+pub(crate) mod particle_ro;
+pub(crate) mod path_ro;
+pub(crate) mod points_ro;
 pub(crate) mod polygon_ro;
 pub(crate) mod rect_ro;
+pub(crate) mod shadow_ro;
 pub(crate) mod text_ro;
+pub(crate) mod tilemap_ro;
@@ -0,0 +1,171 @@
+use nalgebra_glm::Vec2;
+
+use crate::{
+    public::objects::obj_2d::{
+        figure::WrapMode,
+        nine_slice::{NineSlice, SliceMode},
+    },
+    rendering::{
+        pipelines::pipelines_2d::figure_pipeline::FigurePipeline,
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::Vertex2DUvColor,
+    },
+    Transform2D,
+};
+
+use super::figure_ro::FigureRenderObject;
+
+// Start and length of a part of the slice on screen, and of the part of the image it shows
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Segment {
+    start: f32,
+    length: f32,
+    source_start: f32,
+    source_length: f32,
+}
+
+// Nine slices are drawn through the figure pipeline, so they are batched with
+// figures and other nine slices using the same image
+pub(crate) fn nine_slice_render_object(
+    nine_slice: NineSlice,
+    transform: Transform2D,
+    figure_pipeline: &mut FigurePipeline,
+    device_container: &mut DeviceContainer,
+) -> FigureRenderObject {
+    let set = figure_pipeline.get_or_create_set(
+        device_container,
+        &nine_slice.image,
+        nine_slice.filter,
+        WrapMode::Clamp,
+        false,
+    );
+
+    let image_size = Vec2::new(
+        nine_slice.image.size.x as f32,
+        nine_slice.image.size.y as f32,
+    );
+    let color = nine_slice.tint.into();
+
+    let mut vertices = Vec::new();
+    for (x, y) in slice_quads(&nine_slice) {
+        for (corner_x, corner_y) in [(0., 0.), (1., 0.), (0., 1.), (1., 1.)] {
+            let position = Vec2::new(x.start + x.length * corner_x, y.start + y.length * corner_y);
+            let source = Vec2::new(
+                x.source_start + x.source_length * corner_x,
+                y.source_start + y.source_length * corner_y,
+            );
+
+            vertices.push(Vertex2DUvColor {
+                position: transform.transform_point(position).into(),
+                uvCoord: source.component_div(&image_size).into(),
+                color,
+            });
+        }
+    }
+
+    FigureRenderObject::new_with_vertices(set, vertices)
+}
+
+// Horizontal and vertical segment of every quad
+fn slice_quads(nine_slice: &NineSlice) -> Vec<(Segment, Segment)> {
+    let source = nine_slice.source_rect();
+    let origin = nine_slice.position - nine_slice.size / 2.;
+
+    let columns = slice_axis(
+        origin.x,
+        nine_slice.size.x,
+        source.position.x,
+        source.size.x,
+        (nine_slice.left, nine_slice.right),
+    );
+    let rows = slice_axis(
+        origin.y,
+        nine_slice.size.y,
+        source.position.y,
+        source.size.y,
+        (nine_slice.top, nine_slice.bottom),
+    );
+
+    let mut quads = Vec::new();
+    for (row, y) in rows.iter().enumerate() {
+        for (column, x) in columns.iter().enumerate() {
+            let mode = match (column, row) {
+                (1, 1) => nine_slice.center_mode,
+                (1, _) | (_, 1) => nine_slice.edge_mode,
+                _ => SliceMode::Stretch,
+            };
+
+            // Edges only repeat along their length
+            let tile_x = mode == SliceMode::Tile && column == 1;
+            let tile_y = mode == SliceMode::Tile && row == 1;
+            for x in tile(*x, tile_x) {
+                for y in tile(*y, tile_y) {
+                    quads.push((x, y));
+                }
+            }
+        }
+    }
+    quads
+}
+
+// Splits one axis into the start inset, the middle and the end inset
+fn slice_axis(
+    start: f32,
+    length: f32,
+    source_start: f32,
+    source_length: f32,
+    (start_inset, end_inset): (f32, f32),
+) -> [Segment; 3] {
+    let insets = start_inset + end_inset;
+    let scale = if insets > length && insets > 0. {
+        length / insets
+    } else {
+        1.
+    };
+    let (start_length, end_length) = (start_inset * scale, end_inset * scale);
+
+    [
+        Segment {
+            start,
+            length: start_length,
+            source_start,
+            source_length: start_inset,
+        },
+        Segment {
+            start: start + start_length,
+            length: length - start_length - end_length,
+            source_start: source_start + start_inset,
+            source_length: source_length - insets,
+        },
+        Segment {
+            start: start + length - end_length,
+            length: end_length,
+            source_start: source_start + source_length - end_inset,
+            source_length: end_inset,
+        },
+    ]
+}
+
+// Repeats the segment at the size of its source, cutting off the last repetition
+fn tile(segment: Segment, tile: bool) -> Vec<Segment> {
+    if segment.length <= 0. || segment.source_length <= 0. {
+        return Vec::new();
+    }
+    if !tile {
+        return vec![segment];
+    }
+
+    let mut segments = Vec::new();
+    let mut offset = 0.;
+    while offset < segment.length {
+        let length = segment.source_length.min(segment.length - offset);
+        segments.push(Segment {
+            start: segment.start + offset,
+            length,
+            source_start: segment.source_start,
+            source_length: length,
+        });
+        offset += segment.source_length;
+    }
+    segments
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use vulkano::descriptor_set::PersistentDescriptorSet;
+
+use crate::{
+    public::objects::obj_2d::{
+        figure::{FilterMode, WrapMode},
+        particles::{BlendMode, ParticleEmitter, ParticleKind},
+    },
+    rendering::{
+        pipelines::pipelines_2d::{
+            figure_pipeline::FigurePipeline,
+            particle_pipeline::{
+                particle_fs, ParticlePipeline, PARTICLE_SHAPE_CIRCLE, PARTICLE_SHAPE_QUAD,
+            },
+        },
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{transform_constants, ParticleInstance},
+    },
+    Camera2D, Transform2D,
+};
+
+#[derive(Clone)]
+pub(crate) struct ParticleRenderObject {
+    set: Option<Arc<PersistentDescriptorSet>>,
+    instances: Vec<ParticleInstance>,
+    shape: u32,
+    blend: BlendMode,
+    transform: Transform2D,
+}
+
+impl ParticleRenderObject {
+    pub(crate) fn new(
+        emitter: &ParticleEmitter,
+        transform: Transform2D,
+        particle_pipeline: &ParticlePipeline,
+        figure_pipeline: &mut FigurePipeline,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        // Circles and rects sample a white image so all kinds share one shader
+        let (set, shape, uv_region, tint) = match &emitter.kind {
+            ParticleKind::Figure(figure) => (
+                figure_pipeline.get_or_create_set(
+                    device_container,
+                    &figure.image,
+                    figure.filter,
+                    figure.wrap,
+                    figure.mipmaps,
+                ),
+                PARTICLE_SHAPE_QUAD,
+                figure.uv_region(),
+                figure.color(),
+            ),
+            kind => (
+                figure_pipeline.get_or_create_set(
+                    device_container,
+                    &particle_pipeline.white_image,
+                    FilterMode::Nearest,
+                    WrapMode::Clamp,
+                    false,
+                ),
+                match kind {
+                    ParticleKind::Circle => PARTICLE_SHAPE_CIRCLE,
+                    _ => PARTICLE_SHAPE_QUAD,
+                },
+                ([0., 0.], [1., 1.]),
+                [1., 1., 1., 1.],
+            ),
+        };
+
+        let (uv_min, uv_max) = uv_region;
+        let instances = emitter
+            .particles()
+            .iter()
+            .map(|particle| {
+                let color = emitter.color(particle);
+                ParticleInstance {
+                    center: particle.position.into(),
+                    size: emitter.size(particle).into(),
+                    rotation: particle.rotation,
+                    color: [
+                        color.x * tint[0],
+                        color.y * tint[1],
+                        color.z * tint[2],
+                        color.w * tint[3],
+                    ],
+                    uvRect: [uv_min[0], uv_min[1], uv_max[0], uv_max[1]],
+                }
+            })
+            .collect();
+
+        Self::new_with_instances(set, instances, shape, emitter.blend, transform)
+    }
+
+    // Also used for other objects made of many quads, like points
+    pub(crate) fn new_with_instances(
+        set: Option<Arc<PersistentDescriptorSet>>,
+        instances: Vec<ParticleInstance>,
+        shape: u32,
+        blend: BlendMode,
+        transform: Transform2D,
+    ) -> Self {
+        Self {
+            set,
+            instances,
+            shape,
+            blend,
+            transform,
+        }
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        pipeline: &mut ParticlePipeline,
+        device_container: &mut DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) {
+        let set = match &self.set {
+            Some(set) if !self.instances.is_empty() => set.clone(),
+            _ => return,
+        };
+
+        pipeline.draw(
+            device_container,
+            self.instances.clone(),
+            self.create_push_constants(device_container, camera_2d),
+            set,
+            self.blend,
+        );
+    }
+
+    fn create_push_constants(
+        &self,
+        device_container: &DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) -> particle_fs::ty::Constants {
+        let (transform, translation) = transform_constants(camera_2d, &self.transform);
+
+        particle_fs::ty::Constants {
+            resolution: device_container.resolution(),
+            translation,
+            transform,
+            shape: self.shape,
+        }
+    }
+}
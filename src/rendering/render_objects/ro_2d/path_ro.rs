@@ -0,0 +1,123 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::{
+    public::objects::{camera::camera_2d::Camera2D, obj_2d::path::Path, transform_2d::Transform2D},
+    rendering::{
+        geometry::{
+            path::{flatten, SubPath},
+            stroke::path_outline,
+            ROUND_TOLERANCE,
+        },
+        pipelines::pipelines_2d::{
+            line_pipeline::LinePipeline,
+            poly_pipeline::{poly_fs, PolyPipeline},
+        },
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{fill_color, transform_constants, BufferContainer2DEdge},
+    },
+};
+
+use super::{line_ro::LineRenderObject, polygon_ro::create_fill_buffers};
+
+#[derive(Clone)]
+pub(crate) struct PathRenderObject {
+    path: Path,
+    // Buffers together with the origin and size of the bounding box of the fill
+    buffers: Option<(BufferContainer2DEdge, Vec2, Vec2)>,
+    outline: Option<LineRenderObject>,
+    transform: Transform2D,
+}
+
+impl PathRenderObject {
+    pub(crate) fn new(
+        path: Path,
+        transform: Transform2D,
+        camera_zoom: f32,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        // Curves are flattened in world units, so the tolerance shrinks as they get scaled up
+        let screen_scale = transform.max_scale() * camera_zoom.abs();
+        let tolerance = ROUND_TOLERANCE / screen_scale.max(f32::EPSILON);
+        let sub_paths = flatten(&path.commands, tolerance);
+
+        let buffers = Self::create_buffers(&path, &sub_paths, screen_scale, device_container);
+        let outline = path.stroke.as_ref().map(|stroke| {
+            LineRenderObject::new_with_mesh(
+                path_outline(&sub_paths, stroke),
+                transform,
+                device_container,
+            )
+        });
+
+        Self {
+            path,
+            buffers,
+            outline,
+            transform,
+        }
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        pipeline: &mut PolyPipeline,
+        line_pipeline: &mut LinePipeline,
+        device_container: &mut DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) {
+        if let Some((buffers, origin, size)) = &self.buffers {
+            pipeline.draw(
+                device_container,
+                buffers,
+                self.create_push_constants(device_container, camera_2d, origin, size),
+                self.path.fill.as_ref(),
+            );
+        }
+
+        if let Some(outline) = &mut self.outline {
+            outline.draw(line_pipeline, device_container, camera_2d);
+        }
+    }
+
+    #[allow(non_snake_case)]
+    fn create_push_constants(
+        &self,
+        device_container: &DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+        origin: &Vec2,
+        size: &Vec2,
+    ) -> poly_fs::ty::Constants {
+        let (transform, translation) = transform_constants(camera_2d, &self.transform);
+
+        poly_fs::ty::Constants {
+            resolution: device_container.resolution(),
+            position: origin.as_ref().clone(),
+            color: fill_color(Vec4::zeros(), self.path.fill.as_ref()),
+            borderColor: [0.; 4],
+            size: size.as_ref().clone(),
+            borderWidth: 0,
+            translation,
+            transform,
+            antialiasing: device_container.antialiasing() as u32,
+        }
+    }
+
+    fn create_buffers(
+        path: &Path,
+        sub_paths: &[SubPath],
+        screen_scale: f32,
+        device_container: &mut DeviceContainer,
+    ) -> Option<(BufferContainer2DEdge, Vec2, Vec2)> {
+        if path.fill.is_none() {
+            return None;
+        }
+
+        // Every sub path is implicitly closed for filling
+        let contours = sub_paths
+            .iter()
+            .filter(|sub_path| sub_path.points.len() >= 3)
+            .map(|sub_path| sub_path.points.clone())
+            .collect::<Vec<_>>();
+
+        create_fill_buffers(&contours, path.fill_rule, screen_scale, device_container)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{
+    public::objects::obj_2d::{
+        figure::{FilterMode, WrapMode},
+        particles::BlendMode,
+        points::{PointShape, Points},
+    },
+    rendering::{
+        pipelines::pipelines_2d::{
+            figure_pipeline::FigurePipeline,
+            particle_pipeline::{ParticlePipeline, PARTICLE_SHAPE_CIRCLE, PARTICLE_SHAPE_QUAD},
+        },
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::ParticleInstance,
+    },
+    Transform2D,
+};
+
+use super::particle_ro::ParticleRenderObject;
+
+// Points are drawn as instanced quads by the particle pipeline
+pub(crate) fn points_render_object(
+    points: Points,
+    transform: Transform2D,
+    particle_pipeline: &ParticlePipeline,
+    figure_pipeline: &mut FigurePipeline,
+    device_container: &mut DeviceContainer,
+) -> ParticleRenderObject {
+    let set = figure_pipeline.get_or_create_set(
+        device_container,
+        &particle_pipeline.white_image,
+        FilterMode::Nearest,
+        WrapMode::Clamp,
+        false,
+    );
+
+    let shape = match points.shape {
+        PointShape::Square => PARTICLE_SHAPE_QUAD,
+        PointShape::Round => PARTICLE_SHAPE_CIRCLE,
+    };
+
+    let colors = points.colors.as_deref().unwrap_or_default();
+    let instances = points
+        .positions
+        .iter()
+        .enumerate()
+        .map(|(i, position)| ParticleInstance {
+            center: (*position).into(),
+            size: [points.size, points.size],
+            rotation: 0.,
+            color: (*colors.get(i).unwrap_or(&points.color)).into(),
+            uvRect: [0., 0., 1., 1.],
+        })
+        .collect();
+
+    ParticleRenderObject::new_with_instances(set, instances, shape, BlendMode::Alpha, transform)
+}
@@ -1,37 +1,86 @@
-use nalgebra_glm::{dot, Vec2};
+use nalgebra_glm::Vec2;
 
 use crate::{
-    public::objects::{camera::camera_2d::Camera2D, obj_2d::polygon::Polygon},
+    public::objects::{
+        camera::camera_2d::Camera2D,
+        obj_2d::polygon::{FillRule, Polygon},
+        transform_2d::Transform2D,
+    },
     rendering::{
+        geometry::{
+            bounding_box,
+            fringe::{fringe, FRINGE_WIDTH},
+            stroke::polygon_outline,
+            triangulation::triangulate,
+        },
         render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d, BufferContainer2D, Vertex2D},
-        pipelines::pipelines_2d::poly_pipeline::{poly_fs, PolyPipeline},
+        render_objects::shared::{
+            create_buffers_2d_edge, fill_color, transform_constants, BufferContainer2DEdge,
+            Vertex2DEdge,
+        },
+        pipelines::pipelines_2d::{
+            line_pipeline::LinePipeline,
+            poly_pipeline::{poly_fs, PolyPipeline},
+        },
     },
 };
 
+use super::line_ro::LineRenderObject;
+
 #[derive(Clone)]
 pub(crate) struct PolygonRenderObject {
     polygon: Polygon,
-    buffers: BufferContainer2D,
+    // Buffers together with the origin and size of the bounding box of the fill
+    buffers: Option<(BufferContainer2DEdge, Vec2, Vec2)>,
+    outline: Option<LineRenderObject>,
+    transform: Transform2D,
 }
 
 impl PolygonRenderObject {
-    pub(crate) fn new(polygon: Polygon, device_container: &mut DeviceContainer) -> Self {
-        let buffers = Self::create_buffers(&polygon, device_container);
-        Self { polygon, buffers }
+    pub(crate) fn new(
+        polygon: Polygon,
+        transform: Transform2D,
+        camera_zoom: f32,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        let screen_scale = transform.max_scale() * camera_zoom.abs();
+        let buffers = Self::create_buffers(&polygon, screen_scale, device_container);
+        let outline = match (&polygon.border, polygon.validate()) {
+            (Some(border), Ok(())) => Some(LineRenderObject::new_with_mesh(
+                polygon_outline(&polygon, border),
+                transform,
+                device_container,
+            )),
+            _ => None,
+        };
+
+        Self {
+            polygon,
+            buffers,
+            outline,
+            transform,
+        }
     }
 
     pub(crate) fn draw(
         &mut self,
         pipeline: &mut PolyPipeline,
+        line_pipeline: &mut LinePipeline,
         device_container: &mut DeviceContainer,
         camera_2d: Option<&Camera2D>,
     ) {
-        pipeline.draw(
-            device_container,
-            &self.buffers,
-            self.create_push_constants(device_container, camera_2d),
-        );
+        if let Some((buffers, origin, size)) = &self.buffers {
+            pipeline.draw(
+                device_container,
+                buffers,
+                self.create_push_constants(device_container, camera_2d, origin, size),
+                self.polygon.fill.as_ref(),
+            );
+        }
+
+        if let Some(outline) = &mut self.outline {
+            outline.draw(line_pipeline, device_container, camera_2d);
+        }
     }
 
     #[allow(non_snake_case)]
@@ -39,84 +88,93 @@ impl PolygonRenderObject {
         &self,
         device_container: &DeviceContainer,
         camera_2d: Option<&Camera2D>,
+        origin: &Vec2,
+        size: &Vec2,
     ) -> poly_fs::ty::Constants {
         let (borderColor, borderWidth) = match &self.polygon.border {
             Some(border) => (border.color.as_ref().clone(), border.width),
             None => ([0.; 4], 0),
         };
 
-        let cameraPos = match camera_2d {
-            Some(camera_2d) => camera_2d.position.as_ref().clone(),
-            None => [0.; 2],
-        };
+        let (transform, translation) = transform_constants(camera_2d, &self.transform);
 
         poly_fs::ty::Constants {
             resolution: device_container.resolution(),
-            position: [0., 0.],
-            color: self.polygon.color.as_ref().clone(),
+            position: origin.as_ref().clone(),
+            color: fill_color(self.polygon.color, self.polygon.fill.as_ref()),
             borderColor,
-            size: [1., 1.],
+            size: size.as_ref().clone(),
             borderWidth,
-            cameraPos,
+            translation,
+            transform,
+            antialiasing: device_container.antialiasing() as u32,
         }
     }
 
     fn create_buffers(
         polygon: &Polygon,
+        screen_scale: f32,
         device_container: &mut DeviceContainer,
-    ) -> BufferContainer2D {
-        // TODO: Use iter().map() on polygon.points
-        let mut vertices = Vec::new();
-        for p in &polygon.points {
-            vertices.push(Vertex2D {
-                position: [p.x, p.y],
-            })
-        }
-        let mut indices = vec![0, 1, 2];
-
-        for (i, p) in polygon.points.iter().enumerate().skip(3) {
-            let index_0;
-            let index_1;
-            {
-                let last_triangle = &indices[indices.len() - 3..indices.len()];
-                let p0 = polygon.points[last_triangle[0] as usize].clone();
-                let p1 = polygon.points[last_triangle[1] as usize].clone();
-                let p2 = polygon.points[last_triangle[2] as usize].clone();
-
-                let mut lines = Vec::new();
-                if i == 3 {
-                    lines.push((
-                        (last_triangle[0], last_triangle[1]),
-                        dist_to_line(&p0, &p1, &p),
-                    ));
-                }
-                lines.push((
-                    (last_triangle[0], last_triangle[2]),
-                    dist_to_line(&p0, &p2, &p),
-                ));
-                lines.push((
-                    (last_triangle[1], last_triangle[2]),
-                    dist_to_line(&p1, &p2, &p),
-                ));
-                lines.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-                (index_0, index_1) = lines[0].0;
-            }
-            indices.push(index_0);
-            indices.push(index_1);
-            indices.push(i as u32);
+    ) -> Option<(BufferContainer2DEdge, Vec2, Vec2)> {
+        if polygon.validate().is_err() {
+            return None;
         }
 
-        create_buffers_2d(device_container, vertices, indices)
+        create_fill_buffers(
+            &polygon.contours(),
+            polygon.fill_rule,
+            screen_scale,
+            device_container,
+        )
     }
 }
 
-fn dist_to_line(a: &Vec2, b: &Vec2, p: &Vec2) -> f32 {
-    let l2 = (a - b).norm_squared();
-    if l2 == 0. {
-        return (p - a).norm_squared();
+// Triangulates the contours of a fill, surrounded by a fringe for smooth edges when anti-aliasing
+// is enabled. Returns the buffers together with the origin and size of the bounding box.
+pub(crate) fn create_fill_buffers(
+    contours: &[Vec<Vec2>],
+    fill_rule: FillRule,
+    screen_scale: f32,
+    device_container: &mut DeviceContainer,
+) -> Option<(BufferContainer2DEdge, Vec2, Vec2)> {
+    let (positions, mut indices) = triangulate(contours, fill_rule);
+    if indices.is_empty() {
+        return None;
+    }
+
+    // Vertices are stored relative to the bounding box so gradients can be mapped onto it
+    let (origin, size) = bounding_box(&positions);
+    let size = size.map(|s| if s > 0. { s } else { 1. });
+    let mut vertices = positions
+        .iter()
+        .map(|p| {
+            let p = (p - origin).component_div(&size);
+            Vertex2DEdge {
+                position: [p.x, p.y],
+                edgeDistance: 0.,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if device_container.antialiasing() {
+        // The fringe is one pixel wide on screen, so it gets thinner as the fill is scaled up
+        let fringe = fringe(contours, FRINGE_WIDTH / screen_scale.max(f32::EPSILON));
+        let first_index = vertices.len() as u32;
+        indices.extend(fringe.indices.iter().map(|i| i + first_index));
+        vertices.extend(fringe.positions.iter().zip(fringe.distances.iter()).map(
+            |(p, distance)| {
+                let p = (p - origin).component_div(&size);
+                Vertex2DEdge {
+                    position: [p.x, p.y],
+                    edgeDistance: *distance,
+                }
+            },
+        ));
     }
 
-    let t = 0.0_f32.max(1.0_f32.min(dot(&(p - a), &(b - a)) / l2));
-    let proj = a + t * (b - a);
-    (p - proj).norm_squared()
+    Some((
+        create_buffers_2d_edge(device_container, vertices, indices),
+        origin,
+        size,
+    ))
 }
@@ -1,20 +1,37 @@
+use nalgebra_glm::{Vec2, Vec4};
+
 use crate::{
-    public::objects::{camera::camera_2d::Camera2D, obj_2d::rect::Rect},
+    public::objects::{camera::camera_2d::Camera2D, obj_2d::rect::Rect, transform_2d::Transform2D},
     rendering::{
-        pipelines::pipelines_2d::poly_pipeline::{poly_fs, PolyPipeline},
+        geometry::stroke::rect_outline,
+        pipelines::pipelines_2d::{
+            line_pipeline::LinePipeline,
+            rect_pipeline::{rect_fs, RectPipeline},
+        },
         render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d, BufferContainer2D, Vertex2D},
+        render_objects::shared::{
+            create_buffers_2d, fill_color, transform_constants, BufferContainer2D, Vertex2D,
+        },
     },
 };
 
+use super::line_ro::LineRenderObject;
+
 #[derive(Clone)]
 pub(crate) struct RectRenderObject {
     rect: Rect,
     buffers: BufferContainer2D,
+    // Dashed borders are drawn as lines on top of the rect
+    outline: Option<LineRenderObject>,
+    transform: Transform2D,
 }
 
 impl RectRenderObject {
-    pub(crate) fn new(rect: Rect, device_container: &mut DeviceContainer) -> Self {
+    pub(crate) fn new(
+        mut rect: Rect,
+        transform: Transform2D,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
         static mut BUFFERS: Option<BufferContainer2D> = None;
         let buffers;
 
@@ -27,12 +44,30 @@ impl RectRenderObject {
             buffers = BUFFERS.as_ref().unwrap().clone();
         }
 
-        Self { rect, buffers }
+        let outline = match &rect.border {
+            Some(border) if border.style.is_dashed() => Some(LineRenderObject::new_with_mesh(
+                rect_outline(rect.size, clamped_radii(&rect), border),
+                rect_model(&rect, &transform),
+                device_container,
+            )),
+            _ => None,
+        };
+        if outline.is_some() {
+            rect.border = None;
+        }
+
+        Self {
+            rect,
+            buffers,
+            outline,
+            transform,
+        }
     }
 
     pub(crate) fn draw(
         &mut self,
-        pipeline: &mut PolyPipeline,
+        pipeline: &mut RectPipeline,
+        line_pipeline: &mut LinePipeline,
         device_container: &mut DeviceContainer,
         camera_2d: Option<&Camera2D>,
     ) {
@@ -40,7 +75,16 @@ impl RectRenderObject {
             device_container,
             &self.buffers,
             self.create_push_constants(device_container, camera_2d),
+            self.rect.fill.as_ref(),
         );
+
+        if let Some(outline) = &mut self.outline {
+            outline.draw(line_pipeline, device_container, camera_2d);
+        }
+    }
+
+    pub(crate) fn set_size(&mut self, size: Vec2) {
+        self.rect.size = size;
     }
 
     // TODO: Make this function private (and in all other render objects)
@@ -49,25 +93,31 @@ impl RectRenderObject {
         &self,
         device_container: &DeviceContainer,
         camera_2d: Option<&Camera2D>,
-    ) -> poly_fs::ty::Constants {
-        let (borderColor, borderWidth) = match &self.rect.border {
-            Some(border) => (border.color.as_ref().clone(), border.width),
-            None => ([0.; 4], 0),
+    ) -> rect_fs::ty::Constants {
+        let (borderColor, borderWidth, borderOuter) = match &self.rect.border {
+            Some(border) => (
+                border.color.as_ref().clone(),
+                border.width as f32,
+                border.outer_extent(),
+            ),
+            None => ([0.; 4], 0., 0.),
         };
 
-        let cameraPos = match camera_2d {
-            Some(camera_2d) => camera_2d.position.as_ref().clone(),
-            None => [0.; 2],
-        };
+        let cornerRadii = clamped_radii(&self.rect);
+        let (transform, translation) =
+            transform_constants(camera_2d, &rect_model(&self.rect, &self.transform));
 
-        poly_fs::ty::Constants {
-            resolution: device_container.resolution(),
-            position: self.rect.position.as_ref().clone(),
-            color: self.rect.color.as_ref().clone(),
+        rect_fs::ty::Constants {
+            color: fill_color(self.rect.color, self.rect.fill.as_ref()),
             borderColor,
+            cornerRadii: cornerRadii.as_ref().clone(),
+            transform,
+            resolution: device_container.resolution(),
             size: self.rect.size.as_ref().clone(),
+            translation,
             borderWidth,
-            cameraPos,
+            borderOuter,
+            antialiasing: device_container.antialiasing() as u32,
         }
     }
 
@@ -92,3 +142,18 @@ impl RectRenderObject {
         create_buffers_2d(device_container, vertices, indices)
     }
 }
+
+// Radii larger than half the smallest side would make the corners overlap
+pub(crate) fn clamped_radii(rect: &Rect) -> Vec4 {
+    let max_radius = rect.size.x.min(rect.size.y).abs() * 0.5;
+    rect.corner_radii.map(|r| r.clamp(0., max_radius))
+}
+
+// Places the centre of the rect at the origin
+pub(crate) fn rect_model(rect: &Rect, transform: &Transform2D) -> Transform2D {
+    let center_offset = -rect.anchor.offset().component_mul(&rect.size);
+    transform
+        .translate(rect.position)
+        .rotate(rect.rotation)
+        .translate(center_offset)
+}
@@ -0,0 +1,160 @@
+use nalgebra_glm::{Vec2, Vec4};
+
+use crate::{
+    public::objects::{
+        camera::camera_2d::Camera2D,
+        obj_2d::{circle::Circle, rect::Rect},
+        shadow::{Glow, Shadow},
+        transform_2d::Transform2D,
+        Border,
+    },
+    rendering::{
+        pipelines::pipelines_2d::shadow_pipeline::{shadow_fs, ShadowPipeline},
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{
+            create_buffers_2d, transform_constants, BufferContainer2D, Vertex2D,
+        },
+    },
+};
+
+use super::rect_ro::{clamped_radii, rect_model};
+
+// Rounded box casting the shadow, centred on the origin of the model transform
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ShadowShape {
+    model: Transform2D,
+    size: Vec2,
+    corner_radii: Vec4,
+}
+
+impl ShadowShape {
+    pub(crate) fn from_rect(rect: &Rect) -> Self {
+        let outer = outer_extent(rect.border.as_ref());
+        Self {
+            model: rect_model(rect, &Transform2D::identity()),
+            size: rect.size.abs() + Vec2::new(outer, outer) * 2.,
+            corner_radii: clamped_radii(rect).add_scalar(outer),
+        }
+    }
+
+    pub(crate) fn from_circle(circle: &Circle) -> Self {
+        let radius = circle.radius.abs() + outer_extent(circle.border.as_ref());
+        Self {
+            model: Transform2D::from_translation(circle.position),
+            size: Vec2::new(radius, radius) * 2.,
+            corner_radii: Vec4::new(radius, radius, radius, radius),
+        }
+    }
+}
+
+// The border is part of the shape casting the shadow
+fn outer_extent(border: Option<&Border>) -> f32 {
+    border.map(|border| border.outer_extent()).unwrap_or(0.)
+}
+
+#[derive(Clone)]
+pub(crate) struct ShadowRenderObject {
+    shape: ShadowShape,
+    shadow: Shadow,
+    buffers: BufferContainer2D,
+    transform: Transform2D,
+}
+
+impl ShadowRenderObject {
+    pub(crate) fn new(
+        shape: ShadowShape,
+        shadow: Shadow,
+        transform: Transform2D,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        static mut BUFFERS: Option<BufferContainer2D> = None;
+        let buffers;
+
+        // Unsafe is used to change these static values.
+        // This is definitely safe, even thought the compiler can't verify.
+        unsafe {
+            if let None = BUFFERS {
+                BUFFERS = Some(Self::create_buffers(device_container));
+            }
+            buffers = BUFFERS.as_ref().unwrap().clone();
+        }
+
+        Self {
+            shape,
+            shadow,
+            buffers,
+            transform,
+        }
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        pipeline: &mut ShadowPipeline,
+        device_container: &mut DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) {
+        pipeline.draw(
+            device_container,
+            &self.buffers,
+            self.create_push_constants(device_container, camera_2d),
+        );
+    }
+
+    #[allow(non_snake_case)]
+    fn create_push_constants(
+        &self,
+        device_container: &DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) -> shadow_fs::ty::Constants {
+        // The offset is not rotated with the shape, so all shadows fall in the same direction
+        let model = self.transform.translate(self.shadow.offset) * self.shape.model;
+        let (transform, translation) = transform_constants(camera_2d, &model);
+
+        shadow_fs::ty::Constants {
+            color: self.shadow.color.into(),
+            cornerRadii: self.shape.corner_radii.into(),
+            transform,
+            resolution: device_container.resolution(),
+            size: self.shape.size.into(),
+            translation,
+            blur: self.shadow.blur.max(0.),
+            spread: self.shadow.spread,
+        }
+    }
+
+    fn create_buffers(device_container: &mut DeviceContainer) -> BufferContainer2D {
+        let vertices = vec![
+            Vertex2D {
+                position: [-0.5, -0.5],
+            },
+            Vertex2D {
+                position: [0.5, -0.5],
+            },
+            Vertex2D {
+                position: [-0.5, 0.5],
+            },
+            Vertex2D {
+                position: [0.5, 0.5],
+            },
+        ];
+
+        let indices = vec![0, 1, 2, 2, 1, 3];
+
+        create_buffers_2d(device_container, vertices, indices)
+    }
+}
+
+// The glow comes first, so a drop shadow is drawn on top of it
+pub(crate) fn shadow_render_objects(
+    shape: ShadowShape,
+    shadow: Option<&Shadow>,
+    glow: Option<&Glow>,
+    transform: Transform2D,
+    device_container: &mut DeviceContainer,
+) -> Vec<ShadowRenderObject> {
+    glow.map(|glow| glow.shadow())
+        .into_iter()
+        .chain(shadow.cloned())
+        .map(|shadow| ShadowRenderObject::new(shape, shadow, transform, device_container))
+        .collect()
+}
@@ -1,124 +1,190 @@
 use std::sync::Arc;
 
-use fontdue::Metrics;
 use vulkano::descriptor_set::PersistentDescriptorSet;
 
 use crate::{
-    public::objects::{camera::camera_2d::Camera2D, obj_2d::text::Text},
+    public::objects::{
+        camera::camera_2d::Camera2D, obj_2d::text::Text, shadow::Shadow, transform_2d::Transform2D,
+    },
     rendering::{
+        glyph_atlas::AtlasGlyph,
         pipelines::pipelines_2d::text_pipeline::{text_fs, TextPipeline},
         render_containers::device_container::DeviceContainer,
-        render_objects::shared::{create_buffers_2d_uv, BufferContainer2DUv, Vertex2DUv},
+        render_objects::shared::{
+            create_buffers_2d_glyph, transform_constants, BufferContainer2DGlyph, Vertex2DGlyph,
+        },
     },
 };
 
 #[derive(Clone)]
 pub(crate) struct TextRenderObject {
     text: Text,
-    sets: Vec<Arc<PersistentDescriptorSet>>,
-    buffers_vec: Vec<BufferContainer2DUv>,
+    // Atlas the glyphs were packed into, with all glyph quads in one buffer
+    glyphs: Option<(Arc<PersistentDescriptorSet>, BufferContainer2DGlyph)>,
+    // Glyph quads grown to fit the blur of the shadows
+    shadow_buffers: Option<BufferContainer2DGlyph>,
+    transform: Transform2D,
 }
 
 impl TextRenderObject {
     // TODO: Do this differently!!! Don't require the pipeline and devicecontainer
     pub(crate) fn new(
         text: Text,
+        transform: Transform2D,
         text_pipeline: &mut TextPipeline,
         device_container: &mut DeviceContainer,
     ) -> Self {
-        let set_options = text
-            .text
-            .chars()
-            .map(|c| text_pipeline.get_or_create_set(device_container, c, &text.font))
-            .collect::<Vec<_>>();
-
-        let mut x_offset = 0.;
-        let mut buffers_vec = Vec::new();
-        let mut sets = Vec::new();
-        for (set_option, metrics) in set_options {
-            sets.push(match set_option {
-                Some(set) => set,
-                None => {
-                    x_offset += metrics.advance_width;
-                    continue;
+        let (set, glyphs) = match text_pipeline.get_or_create_glyphs(
+            device_container,
+            &text.text,
+            text.font,
+            text.size,
+        ) {
+            Some(set_and_glyphs) => set_and_glyphs,
+            None => {
+                return Self {
+                    text,
+                    glyphs: None,
+                    shadow_buffers: None,
+                    transform,
                 }
-            });
-            buffers_vec.push(create_buffers(device_container, metrics, x_offset as i32));
-            x_offset += metrics.advance_width;
-        }
+            }
+        };
+
+        let margin = shadows(&text).iter().map(shadow_margin).fold(0., f32::max);
+        let buffers = create_buffers(device_container, &glyphs, 0.);
+        let shadow_buffers = if margin > 0. {
+            create_buffers(device_container, &glyphs, margin)
+        } else {
+            None
+        };
+
         Self {
             text,
-            sets,
-            buffers_vec,
+            glyphs: buffers.map(|buffers| (set, buffers)),
+            shadow_buffers,
+            transform,
         }
     }
 
-    #[allow(non_snake_case)]
     pub(crate) fn draw(
         &mut self,
         text_pipeline: &mut TextPipeline,
         device_container: &mut DeviceContainer,
         camera_2d: Option<&Camera2D>,
     ) {
-        let cameraPos = match camera_2d {
-            Some(camera_2d) => camera_2d.position.as_ref().clone(),
-            None => [0.; 2],
+        let (set, buffers) = match &self.glyphs {
+            Some(glyphs) => glyphs,
+            None => return,
         };
 
-        let sets_and_buffers = self
-            .sets
-            .clone()
-            .iter()
-            .zip(self.buffers_vec.iter())
-            .map(|(set, buffers)| (set.clone(), buffers.clone()))
-            .collect::<Vec<_>>();
+        let (transform, translation) = transform_constants(camera_2d, &self.transform);
+        let push_constants = text_fs::ty::Constants {
+            resolution: device_container.resolution(),
+            position: self.text.position.as_ref().clone(),
+            color: self.text.color.as_ref().clone(),
+            translation,
+            transform,
+            blur: 0.,
+            spread: 0.,
+        };
 
-        text_pipeline.draw(
-            device_container,
-            text_fs::ty::Constants {
-                resolution: device_container.resolution(),
-                position: self.text.position.as_ref().clone(),
-                color: self.text.color.as_ref().clone(),
-                cameraPos,
-            },
-            sets_and_buffers,
-        );
+        for shadow in shadows(&self.text) {
+            let shadow_buffers = match &self.shadow_buffers {
+                Some(shadow_buffers) if shadow_margin(&shadow) > 0. => shadow_buffers,
+                _ => buffers,
+            };
+            text_pipeline.draw(
+                device_container,
+                text_fs::ty::Constants {
+                    position: (self.text.position + shadow.offset).into(),
+                    color: shadow.color.into(),
+                    blur: shadow.blur.max(0.),
+                    spread: shadow.spread.max(0.),
+                    ..push_constants
+                },
+                set.clone(),
+                shadow_buffers,
+            );
+        }
+
+        text_pipeline.draw(device_container, push_constants, set.clone(), buffers);
     }
 }
 
+// The glow comes first, so a drop shadow is drawn on top of it
+fn shadows(text: &Text) -> Vec<Shadow> {
+    text.glow
+        .as_ref()
+        .map(|glow| glow.shadow())
+        .into_iter()
+        .chain(text.shadow.clone())
+        .collect()
+}
+
+// How far the blurred and spread glyphs reach outside of their quads, the blur fades out
+// within 3 sigma
+fn shadow_margin(shadow: &Shadow) -> f32 {
+    (shadow.spread.max(0.) + shadow.blur.max(0.) * 1.5).ceil()
+}
+
+// All glyphs of the text in one buffer, so it is drawn at once
 fn create_buffers(
     device_container: &mut DeviceContainer,
-    metrics: Metrics,
-    x_offset: i32,
-) -> BufferContainer2DUv {
-    let x_min = x_offset as f32 + metrics.xmin as f32;
-    let x_max = x_min + metrics.width as f32;
-    let y_max = -metrics.ymin as f32;
-    let y_min = y_max - metrics.height as f32;
-    // let x_min = x_offset as f32 + metrics.bounds.xmin as f32;
-    // let x_max = x_min + metrics.bounds.width as f32;
-    // let y_max = -metrics.bounds.ymin as f32;
-    // let y_min = y_max - metrics.bounds.height as f32;
-    let vertices = vec![
-        Vertex2DUv {
-            position: [x_min, y_min],
-            uvCoord: [0., 0.],
-        },
-        Vertex2DUv {
-            position: [x_max, y_min],
-            uvCoord: [1., 0.],
-        },
-        Vertex2DUv {
-            position: [x_min, y_max],
-            uvCoord: [0., 1.],
-        },
-        Vertex2DUv {
-            position: [x_max, y_max],
-            uvCoord: [1., 1.],
-        },
-    ];
+    glyphs: &[AtlasGlyph],
+    margin: f32,
+) -> Option<BufferContainer2DGlyph> {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut x_offset: f32 = 0.;
+    for glyph in glyphs {
+        let metrics = glyph.metrics;
+        if let Some(atlas_position) = glyph.position {
+            let x_min = x_offset.floor() + metrics.xmin as f32 - margin;
+            let x_max = x_min + metrics.width as f32 + margin * 2.;
+            let y_max = -metrics.ymin as f32 + margin;
+            let y_min = y_max - metrics.height as f32 - margin * 2.;
+            // The atlas is sampled in texels, the rect keeps the margin from reaching into the
+            // neighbouring glyphs
+            let glyph_rect = [
+                atlas_position.x as f32,
+                atlas_position.y as f32,
+                (atlas_position.x + metrics.width as u32) as f32,
+                (atlas_position.y + metrics.height as u32) as f32,
+            ];
+            let (u_min, u_max) = (glyph_rect[0] - margin, glyph_rect[2] + margin);
+            let (v_min, v_max) = (glyph_rect[1] - margin, glyph_rect[3] + margin);
 
-    let indices = vec![0, 1, 2, 2, 1, 3];
+            let first_index = vertices.len() as u32;
+            vertices.extend_from_slice(&[
+                Vertex2DGlyph {
+                    position: [x_min, y_min],
+                    uvCoord: [u_min, v_min],
+                    glyphRect: glyph_rect,
+                },
+                Vertex2DGlyph {
+                    position: [x_max, y_min],
+                    uvCoord: [u_max, v_min],
+                    glyphRect: glyph_rect,
+                },
+                Vertex2DGlyph {
+                    position: [x_min, y_max],
+                    uvCoord: [u_min, v_max],
+                    glyphRect: glyph_rect,
+                },
+                Vertex2DGlyph {
+                    position: [x_max, y_max],
+                    uvCoord: [u_max, v_max],
+                    glyphRect: glyph_rect,
+                },
+            ]);
+            indices.extend([0, 1, 2, 2, 1, 3].iter().map(|i| first_index + i));
+        }
+        x_offset += metrics.advance_width;
+    }
 
-    create_buffers_2d_uv(device_container, vertices, indices)
+    if indices.is_empty() {
+        return None;
+    }
+    Some(create_buffers_2d_glyph(device_container, vertices, indices))
 }
@@ -0,0 +1,337 @@
+use std::sync::Arc;
+
+use nalgebra_glm::{UVec2, Vec2};
+use vulkano::descriptor_set::PersistentDescriptorSet;
+
+use crate::{
+    public::objects::obj_2d::{
+        figure::WrapMode,
+        tilemap::{Tile, TileRotation, Tilemap, CHUNK_SIZE},
+    },
+    rendering::{
+        pipelines::pipelines_2d::figure_pipeline::{figure_fs, FigurePipeline},
+        render_containers::device_container::DeviceContainer,
+        render_objects::shared::{
+            create_buffers_2d_uv_color, transform_constants, BufferContainer2DUvColor,
+            Vertex2DUvColor,
+        },
+    },
+    Camera2D, Transform2D,
+};
+
+use super::figure_ro::QUAD_INDICES;
+
+// Corners of a tile in the order of QUAD_INDICES
+const TILE_CORNERS: [[f32; 2]; 4] = [[0., 0.], [1., 0.], [0., 1.], [1., 1.]];
+
+// The visible chunks of every visible layer, layers in order. The chunks stay on the gpu,
+// only the transform is passed every frame.
+#[derive(Clone)]
+pub(crate) struct TilemapRenderObject {
+    set: Option<Arc<PersistentDescriptorSet>>,
+    chunks: Vec<BufferContainer2DUvColor>,
+    transform: Transform2D,
+}
+
+impl TilemapRenderObject {
+    // screen_transform maps the map to screen pixels and is used to skip chunks outside the view
+    pub(crate) fn new(
+        tilemap: &mut Tilemap,
+        transform: Transform2D,
+        screen_transform: Transform2D,
+        resolution: Vec2,
+        figure_pipeline: &mut FigurePipeline,
+        device_container: &mut DeviceContainer,
+    ) -> Self {
+        update_chunks(tilemap, device_container);
+
+        let set = figure_pipeline.get_or_create_set(
+            device_container,
+            &tilemap.tileset,
+            tilemap.filter,
+            WrapMode::Clamp,
+            false,
+        );
+
+        let transform = transform * Transform2D::from_translation(tilemap.position);
+        let screen_transform = screen_transform * Transform2D::from_translation(tilemap.position);
+
+        let chunk_count = tilemap.chunk_count();
+        let visible_chunks = (0..chunk_count.y)
+            .flat_map(|y| (0..chunk_count.x).map(move |x| UVec2::new(x, y)))
+            .filter(|&chunk| chunk_visible(tilemap, chunk, screen_transform, resolution))
+            .map(|chunk| tilemap.chunk_index(chunk.x, chunk.y))
+            .collect::<Vec<_>>();
+
+        let chunks = tilemap
+            .layers()
+            .iter()
+            .filter(|layer| layer.visible)
+            .flat_map(|layer| {
+                visible_chunks
+                    .iter()
+                    .filter_map(|&chunk| layer.chunks[chunk].buffers.clone())
+            })
+            .collect();
+
+        Self {
+            set,
+            chunks,
+            transform,
+        }
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        pipeline: &mut FigurePipeline,
+        device_container: &mut DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) {
+        let set = match &self.set {
+            Some(set) => set.clone(),
+            None => return,
+        };
+
+        let push_constants = self.create_push_constants(device_container, camera_2d);
+        for buffers in &self.chunks {
+            pipeline.draw_buffers(device_container, buffers, push_constants, set.clone());
+        }
+    }
+
+    fn create_push_constants(
+        &self,
+        device_container: &DeviceContainer,
+        camera_2d: Option<&Camera2D>,
+    ) -> figure_fs::ty::Constants {
+        let (transform, translation) = transform_constants(camera_2d, &self.transform);
+
+        figure_fs::ty::Constants {
+            resolution: device_container.resolution(),
+            translation,
+            transform,
+        }
+    }
+}
+
+// Rebuilds the meshes of the chunks whose tiles changed, all chunks of a layer whose tint
+// changed, or all of them when the tileset or the tile sizes changed
+fn update_chunks(tilemap: &mut Tilemap, device_container: &mut DeviceContainer) {
+    let built_with = (tilemap.tileset.size(), tilemap.tile_size, tilemap.cell_size);
+    let rebuild_all = tilemap.built_with != Some(built_with);
+    tilemap.built_with = Some(built_with);
+
+    let chunk_count = tilemap.chunk_count();
+    let map_size = tilemap.size();
+    let (tileset_size, tile_size, cell_size) = built_with;
+    let columns = if tile_size.x == 0 {
+        0
+    } else {
+        tileset_size.x / tile_size.x
+    };
+    let rows = if tile_size.y == 0 {
+        0
+    } else {
+        tileset_size.y / tile_size.y
+    };
+
+    for layer in tilemap.layers_mut() {
+        let rebuild_layer = rebuild_all || layer.built_tint != Some(layer.tint);
+        layer.built_tint = Some(layer.tint);
+        let color = layer.tint.into();
+
+        for chunk_y in 0..chunk_count.y {
+            for chunk_x in 0..chunk_count.x {
+                let index = (chunk_y * chunk_count.x + chunk_x) as usize;
+                if !rebuild_layer && !layer.chunks[index].dirty {
+                    continue;
+                }
+
+                let mut vertices = Vec::new();
+                let end_x = ((chunk_x + 1) * CHUNK_SIZE).min(map_size.x);
+                let end_y = ((chunk_y + 1) * CHUNK_SIZE).min(map_size.y);
+                for y in chunk_y * CHUNK_SIZE..end_y {
+                    for x in chunk_x * CHUNK_SIZE..end_x {
+                        let tile = match layer.tile(map_size.x, x, y) {
+                            Some(tile) if tile.index < columns * rows => tile,
+                            _ => continue,
+                        };
+
+                        let column = tile.index % columns;
+                        let row = tile.index / columns;
+                        vertices.extend(TILE_CORNERS.iter().map(|corner| {
+                            let source = source_corner(&tile, *corner);
+                            Vertex2DUvColor {
+                                position: [
+                                    (x as f32 + corner[0]) * cell_size.x,
+                                    (y as f32 + corner[1]) * cell_size.y,
+                                ],
+                                uvCoord: [
+                                    (column as f32 + source[0]) * tile_size.x as f32
+                                        / tileset_size.x as f32,
+                                    (row as f32 + source[1]) * tile_size.y as f32
+                                        / tileset_size.y as f32,
+                                ],
+                                color,
+                            }
+                        }));
+                    }
+                }
+
+                let indices = (0..vertices.len() as u32 / 4)
+                    .flat_map(|quad| QUAD_INDICES.iter().map(move |index| quad * 4 + index))
+                    .collect::<Vec<_>>();
+                layer.chunks[index].buffers = if vertices.is_empty() {
+                    None
+                } else {
+                    Some(create_buffers_2d_uv_color(
+                        device_container,
+                        vertices,
+                        indices,
+                    ))
+                };
+                layer.chunks[index].dirty = false;
+            }
+        }
+    }
+}
+
+// Corner of the tile in the tileset that ends up at corner of the cell
+fn source_corner(tile: &Tile, corner: [f32; 2]) -> [f32; 2] {
+    let x = if tile.flip_x {
+        1. - corner[0]
+    } else {
+        corner[0]
+    };
+    let y = if tile.flip_y {
+        1. - corner[1]
+    } else {
+        corner[1]
+    };
+
+    match tile.rotation {
+        TileRotation::None => [x, y],
+        TileRotation::Quarter => [y, 1. - x],
+        TileRotation::Half => [1. - x, 1. - y],
+        TileRotation::ThreeQuarters => [1. - y, x],
+    }
+}
+
+fn chunk_visible(
+    tilemap: &Tilemap,
+    chunk: UVec2,
+    screen_transform: Transform2D,
+    resolution: Vec2,
+) -> bool {
+    let chunk_tiles =
+        (tilemap.size() - chunk * CHUNK_SIZE).inf(&UVec2::new(CHUNK_SIZE, CHUNK_SIZE));
+    let min = Vec2::new(chunk.x as f32, chunk.y as f32).component_mul(&tilemap.cell_size)
+        * CHUNK_SIZE as f32;
+    let size =
+        Vec2::new(chunk_tiles.x as f32, chunk_tiles.y as f32).component_mul(&tilemap.cell_size);
+
+    let corners = TILE_CORNERS.map(|corner| {
+        let corner = Vec2::new(corner[0], corner[1]);
+        screen_transform.transform_point(min + corner.component_mul(&size))
+    });
+    let screen_min = corners
+        .iter()
+        .fold(corners[0], |min, corner| min.inf(corner));
+    let screen_max = corners
+        .iter()
+        .fold(corners[0], |max, corner| max.sup(corner));
+
+    // The camera only moves after the objects are queued, so a margin keeps chunks
+    // at the edge from popping in a frame late
+    let margin = resolution * 0.25;
+    screen_max.x >= -margin.x
+        && screen_max.y >= -margin.y
+        && screen_min.x <= resolution.x + margin.x
+        && screen_min.y <= resolution.y + margin.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{source_corner, TILE_CORNERS};
+    use crate::public::objects::obj_2d::tilemap::{Tile, TileRotation};
+
+    const ROTATIONS: [TileRotation; 4] = [
+        TileRotation::None,
+        TileRotation::Quarter,
+        TileRotation::Half,
+        TileRotation::ThreeQuarters,
+    ];
+
+    // Where a corner of the tileset tile is drawn in the cell, rotating clockwise and then
+    // flipping
+    fn drawn_corner(tile: &Tile, corner: [f32; 2]) -> [f32; 2] {
+        let quarters = ROTATIONS.iter().position(|r| *r == tile.rotation).unwrap();
+        let [mut x, mut y] = corner;
+        for _ in 0..quarters {
+            [x, y] = [1. - y, x];
+        }
+        if tile.flip_x {
+            x = 1. - x;
+        }
+        if tile.flip_y {
+            y = 1. - y;
+        }
+        [x, y]
+    }
+
+    fn tiles() -> impl Iterator<Item = Tile> {
+        ROTATIONS.into_iter().flat_map(|rotation| {
+            [(false, false), (true, false), (false, true), (true, true)]
+                .into_iter()
+                .map(move |(flip_x, flip_y)| {
+                    Tile::new(0)
+                        .with_rotation(rotation)
+                        .with_flip_x(flip_x)
+                        .with_flip_y(flip_y)
+                })
+        })
+    }
+
+    #[test]
+    fn source_corner_undoes_rotation_and_flip() {
+        for tile in tiles() {
+            for corner in TILE_CORNERS {
+                assert_eq!(
+                    source_corner(&tile, drawn_corner(&tile, corner)),
+                    corner,
+                    "{:?}",
+                    tile
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn source_corner_gives_eight_orientations() {
+        let mut orientations = tiles()
+            .map(|tile| TILE_CORNERS.map(|corner| source_corner(&tile, corner)))
+            .collect::<Vec<_>>();
+        orientations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        orientations.dedup();
+        assert_eq!(orientations.len(), 8);
+    }
+
+    #[test]
+    fn source_corner_examples() {
+        let tile = Tile::new(0);
+        assert_eq!(source_corner(&tile, [1., 0.]), [1., 0.]);
+
+        // The top left of the cell shows the bottom left of the tile after a quarter turn
+        let tile = tile.with_rotation(TileRotation::Quarter);
+        assert_eq!(source_corner(&tile, [0., 0.]), [0., 1.]);
+
+        // Flipped after rotating, so the top right of the cell shows it instead
+        let tile = tile.with_flip_x(true);
+        assert_eq!(source_corner(&tile, [1., 0.]), [0., 1.]);
+
+        let tile = Tile::new(0)
+            .with_rotation(TileRotation::Half)
+            .with_flip_x(true)
+            .with_flip_y(true);
+        assert_eq!(source_corner(&tile, [1., 0.]), [1., 0.]);
+    }
+}
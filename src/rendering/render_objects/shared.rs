@@ -1,14 +1,34 @@
 use std::sync::Arc;
 
 use bytemuck::{Pod, Zeroable};
+use nalgebra_glm::Vec4;
 use vulkano::{
-    buffer::{BufferUsage, DeviceLocalBuffer},
+    buffer::{BufferUsage, CpuAccessibleBuffer, DeviceLocalBuffer},
     command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, PrimaryCommandBufferAbstract},
+    descriptor_set::{layout::DescriptorSetLayout, PersistentDescriptorSet, WriteDescriptorSet},
     impl_vertex,
     sync::GpuFuture,
 };
 
-use crate::rendering::render_containers::device_container::DeviceContainer;
+use crate::{
+    public::objects::{
+        camera::camera_2d::Camera2D,
+        fill::{ColorStop, Fill},
+        transform_2d::Transform2D,
+    },
+    rendering::{lru_cache::LruCache, render_containers::device_container::DeviceContainer},
+};
+
+// Has to match MAX_STOPS in fill.glsl
+pub(crate) const MAX_GRADIENT_STOPS: usize = 8;
+
+// Gradient fill sets kept per pipeline
+const MAX_CACHED_FILL_SETS: usize = 256;
+
+const FILL_KIND_SOLID: u32 = 0;
+const FILL_KIND_LINEAR: u32 = 1;
+const FILL_KIND_RADIAL: u32 = 2;
+const FILL_KIND_CONIC: u32 = 3;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
@@ -17,14 +37,204 @@ pub(crate) struct Vertex2D {
 }
 impl_vertex!(Vertex2D, position);
 
+// Vertex of a glyph quad in the glyph atlas, the uv and the rect of the glyph are in texels
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+pub(crate) struct Vertex2DGlyph {
+    pub(crate) position: [f32; 2],
+    pub(crate) uvCoord: [f32; 2],
+    pub(crate) glyphRect: [f32; 4],
+}
+impl_vertex!(Vertex2DGlyph, position, uvCoord, glyphRect);
+
 #[allow(non_snake_case)]
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
-pub(crate) struct Vertex2DUv {
+pub(crate) struct Vertex2DUvColor {
     pub(crate) position: [f32; 2],
     pub(crate) uvCoord: [f32; 2],
+    pub(crate) color: [f32; 4],
+}
+impl_vertex!(Vertex2DUvColor, position, uvCoord, color);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+pub(crate) struct Vertex2DColor {
+    pub(crate) position: [f32; 2],
+    pub(crate) color: [f32; 4],
+}
+impl_vertex!(Vertex2DColor, position, color);
+
+// Vertex of a fill, with its distance outside of the fill for smoothing the edges
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+pub(crate) struct Vertex2DEdge {
+    pub(crate) position: [f32; 2],
+    pub(crate) edgeDistance: f32,
+}
+impl_vertex!(Vertex2DEdge, position, edgeDistance);
+
+// Per instance data of the particle pipeline
+#[allow(non_snake_case)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+pub(crate) struct ParticleInstance {
+    pub(crate) center: [f32; 2],
+    pub(crate) size: [f32; 2],
+    pub(crate) rotation: f32,
+    pub(crate) color: [f32; 4],
+    pub(crate) uvRect: [f32; 4],
+}
+impl_vertex!(ParticleInstance, center, size, rotation, color, uvRect);
+
+// Combines the camera with the transform of an object into the mat2 and translation
+// the 2D vertex shaders use to get from object to screen coordinates
+pub(crate) fn transform_constants(
+    camera_2d: Option<&Camera2D>,
+    transform: &Transform2D,
+) -> ([[f32; 2]; 2], [f32; 2]) {
+    let transform = match camera_2d {
+        Some(camera_2d) => camera_2d.view_transform() * *transform,
+        None => *transform,
+    };
+    (transform.linear(), transform.translation())
+}
+
+// Layout of the Fill uniform block (std140) of the 2D fragment shaders.
+// Offsets are packed four to a vec4 since std140 pads every array element to 16 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+pub(crate) struct FillUniform {
+    colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+    offsets: [[f32; 4]; MAX_GRADIENT_STOPS / 4],
+    // Start and end for linear gradients, center and radius or start angle otherwise
+    params: [f32; 4],
+    // Kind and stop count
+    info: [u32; 4],
+}
+
+impl FillUniform {
+    // Solid fills are drawn with the color push constant, so they don't need any data here
+    // and return None
+    pub(crate) fn new(fill: Option<&Fill>) -> Option<Self> {
+        let (kind, params, stops) = match fill {
+            Some(Fill::LinearGradient { start, end, stops }) => {
+                (FILL_KIND_LINEAR, [start.x, start.y, end.x, end.y], stops)
+            }
+            Some(Fill::RadialGradient {
+                center,
+                radius,
+                stops,
+            }) => (FILL_KIND_RADIAL, [center.x, center.y, *radius, 0.], stops),
+            Some(Fill::ConicGradient {
+                center,
+                start_angle,
+                stops,
+            }) => (
+                FILL_KIND_CONIC,
+                [center.x, center.y, *start_angle, 0.],
+                stops,
+            ),
+            Some(Fill::Solid(_)) | None => return None,
+        };
+
+        if stops.is_empty() {
+            return None;
+        }
+
+        let mut stops = stops
+            .iter()
+            .take(MAX_GRADIENT_STOPS)
+            .copied()
+            .collect::<Vec<ColorStop>>();
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+
+        let mut uniform = Self {
+            params,
+            info: [kind, stops.len() as u32, 0, 0],
+            ..Default::default()
+        };
+        for (i, stop) in stops.iter().enumerate() {
+            uniform.colors[i] = stop.color.as_ref().clone();
+            uniform.offsets[i / 4][i % 4] = stop.offset.clamp(0., 1.);
+        }
+        Some(uniform)
+    }
+}
+
+// The color used for the color push constant, gradients ignore it
+pub(crate) fn fill_color(color: Vec4, fill: Option<&Fill>) -> [f32; 4] {
+    let color = match fill {
+        Some(Fill::Solid(solid)) => *solid,
+        _ => color,
+    };
+    color.as_ref().clone()
+}
+
+fn create_fill_set(
+    device_container: &DeviceContainer,
+    layout: &Arc<DescriptorSetLayout>,
+    fill: FillUniform,
+) -> Arc<PersistentDescriptorSet> {
+    let buffer = CpuAccessibleBuffer::from_data(
+        device_container.memory_allocator().as_ref(),
+        BufferUsage {
+            uniform_buffer: true,
+            ..Default::default()
+        },
+        false,
+        fill,
+    )
+    .unwrap();
+
+    PersistentDescriptorSet::new(
+        device_container.descriptor_set_allocator().as_ref(),
+        layout.clone(),
+        [WriteDescriptorSet::buffer(0, buffer)],
+    )
+    .unwrap()
+}
+
+// Fill sets of a pipeline. Solid fills share one set, gradients get a set of their own that is
+// kept by the contents of its uniform, so a gradient drawn every frame is only uploaded once.
+pub(crate) struct FillSets {
+    solid: Arc<PersistentDescriptorSet>,
+    gradients: LruCache<Vec<u8>, Arc<PersistentDescriptorSet>>,
+}
+
+impl FillSets {
+    pub(crate) fn new(
+        device_container: &DeviceContainer,
+        layout: &Arc<DescriptorSetLayout>,
+    ) -> Self {
+        Self {
+            solid: create_fill_set(device_container, layout, FillUniform::default()),
+            gradients: LruCache::new(MAX_CACHED_FILL_SETS),
+        }
+    }
+
+    pub(crate) fn get(
+        &mut self,
+        device_container: &DeviceContainer,
+        layout: &Arc<DescriptorSetLayout>,
+        fill: Option<&Fill>,
+    ) -> Arc<PersistentDescriptorSet> {
+        let uniform = match FillUniform::new(fill) {
+            Some(uniform) => uniform,
+            None => return self.solid.clone(),
+        };
+
+        let key = bytemuck::bytes_of(&uniform).to_vec();
+        if let Some(set) = self.gradients.get(&key) {
+            return set.clone();
+        }
+        let set = create_fill_set(device_container, layout, uniform);
+        self.gradients.insert(key, set.clone());
+        set
+    }
 }
-impl_vertex!(Vertex2DUv, position, uvCoord);
 
 #[derive(Clone, Debug)]
 pub(crate) struct BufferContainer2D {
@@ -33,8 +243,26 @@ pub(crate) struct BufferContainer2D {
 }
 
 #[derive(Clone, Debug)]
-pub(crate) struct BufferContainer2DUv {
-    pub(crate) vertex_buffer: Arc<DeviceLocalBuffer<[Vertex2DUv]>>,
+pub(crate) struct BufferContainer2DUvColor {
+    pub(crate) vertex_buffer: Arc<DeviceLocalBuffer<[Vertex2DUvColor]>>,
+    pub(crate) index_buffer: Arc<DeviceLocalBuffer<[u32]>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct BufferContainer2DGlyph {
+    pub(crate) vertex_buffer: Arc<DeviceLocalBuffer<[Vertex2DGlyph]>>,
+    pub(crate) index_buffer: Arc<DeviceLocalBuffer<[u32]>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct BufferContainer2DColor {
+    pub(crate) vertex_buffer: Arc<DeviceLocalBuffer<[Vertex2DColor]>>,
+    pub(crate) index_buffer: Arc<DeviceLocalBuffer<[u32]>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct BufferContainer2DEdge {
+    pub(crate) vertex_buffer: Arc<DeviceLocalBuffer<[Vertex2DEdge]>>,
     pub(crate) index_buffer: Arc<DeviceLocalBuffer<[u32]>>,
 }
 
@@ -88,11 +316,11 @@ pub(crate) fn create_buffers_2d(
     }
 }
 
-pub(crate) fn create_buffers_2d_uv(
+pub(crate) fn create_buffers_2d_uv_color(
     device_container: &mut DeviceContainer,
-    vertices: Vec<Vertex2DUv>,
+    vertices: Vec<Vertex2DUvColor>,
     indices: Vec<u32>,
-) -> BufferContainer2DUv {
+) -> BufferContainer2DUvColor {
     let memory_allocator = device_container.memory_allocator();
     let builder = device_container.get_command_buffer_builder();
     let vertex_buffer = DeviceLocalBuffer::from_iter(
@@ -117,7 +345,112 @@ pub(crate) fn create_buffers_2d_uv(
     )
     .unwrap();
 
-    BufferContainer2DUv {
+    BufferContainer2DUvColor {
+        vertex_buffer,
+        index_buffer,
+    }
+}
+
+pub(crate) fn create_buffers_2d_glyph(
+    device_container: &mut DeviceContainer,
+    vertices: Vec<Vertex2DGlyph>,
+    indices: Vec<u32>,
+) -> BufferContainer2DGlyph {
+    let memory_allocator = device_container.memory_allocator();
+    let builder = device_container.get_command_buffer_builder();
+    let vertex_buffer = DeviceLocalBuffer::from_iter(
+        memory_allocator.as_ref(),
+        vertices,
+        BufferUsage {
+            vertex_buffer: true,
+            ..Default::default()
+        },
+        builder,
+    )
+    .unwrap();
+
+    let index_buffer = DeviceLocalBuffer::from_iter(
+        memory_allocator.as_ref(),
+        indices,
+        BufferUsage {
+            index_buffer: true,
+            ..Default::default()
+        },
+        builder,
+    )
+    .unwrap();
+
+    BufferContainer2DGlyph {
+        vertex_buffer,
+        index_buffer,
+    }
+}
+
+pub(crate) fn create_buffers_2d_color(
+    device_container: &mut DeviceContainer,
+    vertices: Vec<Vertex2DColor>,
+    indices: Vec<u32>,
+) -> BufferContainer2DColor {
+    let memory_allocator = device_container.memory_allocator();
+    let builder = device_container.get_command_buffer_builder();
+    let vertex_buffer = DeviceLocalBuffer::from_iter(
+        memory_allocator.as_ref(),
+        vertices,
+        BufferUsage {
+            vertex_buffer: true,
+            ..Default::default()
+        },
+        builder,
+    )
+    .unwrap();
+
+    let index_buffer = DeviceLocalBuffer::from_iter(
+        memory_allocator.as_ref(),
+        indices,
+        BufferUsage {
+            index_buffer: true,
+            ..Default::default()
+        },
+        builder,
+    )
+    .unwrap();
+
+    BufferContainer2DColor {
+        vertex_buffer,
+        index_buffer,
+    }
+}
+
+pub(crate) fn create_buffers_2d_edge(
+    device_container: &mut DeviceContainer,
+    vertices: Vec<Vertex2DEdge>,
+    indices: Vec<u32>,
+) -> BufferContainer2DEdge {
+    let memory_allocator = device_container.memory_allocator();
+    let builder = device_container.get_command_buffer_builder();
+    let vertex_buffer = DeviceLocalBuffer::from_iter(
+        memory_allocator.as_ref(),
+        vertices,
+        BufferUsage {
+            vertex_buffer: true,
+            ..Default::default()
+        },
+        builder,
+    )
+    .unwrap();
+
+    let index_buffer = DeviceLocalBuffer::from_iter(
+        memory_allocator.as_ref(),
+        indices,
+        BufferUsage {
+            index_buffer: true,
+            ..Default::default()
+        },
+        builder,
+    )
+    .unwrap();
+
+    BufferContainer2DEdge {
         vertex_buffer,
         index_buffer,
     }
@@ -158,3 +491,73 @@ pub(super) fn create_buffers_3d(
         index_buffer,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{Vec2, Vec4};
+
+    use super::{FillUniform, FILL_KIND_CONIC, FILL_KIND_LINEAR, FILL_KIND_RADIAL};
+    use crate::public::objects::fill::Fill;
+
+    fn gray(value: f32) -> Vec4 {
+        Vec4::new(value, value, value, 1.)
+    }
+
+    #[test]
+    fn solid_fills_have_no_uniform() {
+        assert!(FillUniform::new(None).is_none());
+        assert!(FillUniform::new(Some(&Fill::Solid(gray(1.)))).is_none());
+        let without_stops = Fill::linear_gradient(Vec2::new(0., 0.), Vec2::new(1., 0.));
+        assert!(FillUniform::new(Some(&without_stops)).is_none());
+    }
+
+    #[test]
+    fn stores_kind_and_params() {
+        let fill =
+            Fill::linear_gradient(Vec2::new(0.1, 0.2), Vec2::new(0.3, 0.4)).with_stop(0., gray(0.));
+        let uniform = FillUniform::new(Some(&fill)).unwrap();
+        assert_eq!(uniform.info, [FILL_KIND_LINEAR, 1, 0, 0]);
+        assert_eq!(uniform.params, [0.1, 0.2, 0.3, 0.4]);
+
+        let fill = Fill::radial_gradient(Vec2::new(0.5, 0.6), 0.7).with_stop(0., gray(0.));
+        let uniform = FillUniform::new(Some(&fill)).unwrap();
+        assert_eq!(uniform.info[0], FILL_KIND_RADIAL);
+        assert_eq!(uniform.params, [0.5, 0.6, 0.7, 0.]);
+
+        let fill = Fill::conic_gradient(Vec2::new(0.5, 0.5), 1.5).with_stop(0., gray(0.));
+        let uniform = FillUniform::new(Some(&fill)).unwrap();
+        assert_eq!(uniform.info[0], FILL_KIND_CONIC);
+        assert_eq!(uniform.params, [0.5, 0.5, 1.5, 0.]);
+    }
+
+    #[test]
+    fn sorts_and_clamps_stops() {
+        let fill = Fill::vertical(gray(0.), gray(1.))
+            .with_stop(1.5, gray(0.8))
+            .with_stop(0.5, gray(0.5))
+            .with_stop(-0.5, gray(0.2));
+        let uniform = FillUniform::new(Some(&fill)).unwrap();
+        assert_eq!(uniform.info[1], 5);
+        assert_eq!(uniform.offsets[0], [0., 0., 0.5, 1.]);
+        assert_eq!(uniform.offsets[1], [1., 0., 0., 0.]);
+        assert_eq!(uniform.colors[0], [0.2, 0.2, 0.2, 1.]);
+        assert_eq!(uniform.colors[1], [0., 0., 0., 1.]);
+        assert_eq!(uniform.colors[2], [0.5, 0.5, 0.5, 1.]);
+    }
+
+    #[test]
+    fn keeps_the_first_eight_stops() {
+        // Added from last to first, the last two are dropped before sorting
+        let fill = (0..10).fold(
+            Fill::linear_gradient(Vec2::new(0., 0.), Vec2::new(1., 0.)),
+            |fill, i| fill.with_stop(1. - i as f32 * 0.1, gray(i as f32 * 0.1)),
+        );
+        let uniform = FillUniform::new(Some(&fill)).unwrap();
+        assert_eq!(uniform.info[1], 8);
+        let offsets = uniform.offsets.concat();
+        for (offset, expected) in offsets.iter().zip([0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.]) {
+            assert!((offset - expected).abs() < 1e-6, "{:?}", offsets);
+        }
+        assert_eq!(uniform.colors[7], [0., 0., 0., 1.]);
+    }
+}
@@ -1,24 +1,87 @@
 #version 450
 
 layout(push_constant) uniform Constants {
-    uvec2 resolution;
-    vec2 position;
     vec4 color;
     vec4 borderColor;
-    uint borderWidth;
-    float radius;
-    vec2 cameraPos;
+    mat2 transform;
+    uvec2 resolution;
+    vec2 radii;
+    vec2 translation;
+    float innerScale;
+    float startAngle;
+    float endAngle;
+    float borderWidth;
+    float borderOuter;
+    uint antialiasing;
 } pc;
 
-layout(location=0) out vec4 f_color;
+#include <fill.glsl>
+
+layout(location = 0) in vec2 fLocalPos;
+
+layout(location = 0) out vec4 f_color;
+
+float ellipseDistance(vec2 p, vec2 radii);
+float wedgeDistance(vec2 p, float startAngle, float endAngle);
+float coverage(float dist, float edgeWidth);
 
 void main() {
-    float dist_center = distance(gl_FragCoord.xy, pc.position + pc.cameraPos);
-    if (dist_center > pc.radius + pc.borderWidth) {
+    float dist = ellipseDistance(fLocalPos, pc.radii);
+    if (pc.innerScale > 0.) {
+        dist = max(dist, -ellipseDistance(fLocalPos, pc.radii * pc.innerScale));
+    }
+    if (pc.endAngle - pc.startAngle < TAU) {
+        dist = max(dist, wedgeDistance(fLocalPos, pc.startAngle, pc.endAngle));
+    }
+
+    float edgeWidth = pc.antialiasing != 0 ? fwidth(dist) : 0.;
+
+    float shapeCoverage = coverage(dist - pc.borderOuter, edgeWidth);
+    if (shapeCoverage <= 0.) {
         discard;
-    } else if (dist_center > pc.radius) {
-        f_color = pc.borderColor;
-    } else {
-        f_color = pc.color;
     }
-}
\ No newline at end of file
+
+    vec4 color = fillColor(fLocalPos / (2. * pc.radii) + 0.5, pc.color);
+    if (pc.borderWidth > 0.) {
+        float fillCoverage = coverage(dist - pc.borderOuter + pc.borderWidth, edgeWidth);
+        color = mix(pc.borderColor, color, fillCoverage);
+    }
+    f_color = vec4(color.rgb, color.a * shapeCoverage);
+}
+
+// Approximation that is exact for circles
+float ellipseDistance(vec2 p, vec2 radii) {
+    float k0 = length(p / radii);
+    float k1 = length(p / (radii * radii));
+    if (k1 == 0.) {
+        return -min(radii.x, radii.y);
+    }
+    return k0 * (k0 - 1.) / k1;
+}
+
+// Signed distance to the infinite wedge between the two angles
+float wedgeDistance(vec2 p, float startAngle, float endAngle) {
+    float halfAperture = (endAngle - startAngle) * 0.5;
+    float mid = startAngle + halfAperture;
+
+    // Rotate the wedge so it is mirrored around the positive x axis
+    float s = sin(-mid);
+    float c = cos(-mid);
+    vec2 q = mat2(c, s, -s, c) * p;
+    q.y = abs(q.y);
+
+    vec2 edge = vec2(cos(halfAperture), sin(halfAperture));
+    vec2 normal = vec2(-edge.y, edge.x);
+    float side = dot(q, normal);
+    float edgeDist = dot(q, edge) > 0. ? abs(side) : length(q);
+    return side > 0. ? edgeDist : -edgeDist;
+}
+
+// Part of the pixel inside of the edge at a signed distance of dist, edgeWidth is the change of
+// the distance over one pixel. Without a width the edge is hard.
+float coverage(float dist, float edgeWidth) {
+    if (edgeWidth <= 0.) {
+        return dist > 0. ? 0. : 1.;
+    }
+    return clamp(0.5 - dist / edgeWidth, 0., 1.);
+}
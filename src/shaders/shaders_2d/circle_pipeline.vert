@@ -1,24 +1,36 @@
 #version 450
 
 layout(push_constant) uniform Constants {
-    uvec2 resolution;
-    vec2 position;
     vec4 color;
     vec4 borderColor;
-    uint borderWidth;
-    float radius;
-    vec2 cameraPos;
+    mat2 transform;
+    uvec2 resolution;
+    vec2 radii;
+    vec2 translation;
+    float innerScale;
+    float startAngle;
+    float endAngle;
+    float borderWidth;
+    float borderOuter;
+    uint antialiasing;
 } pc;
 
 layout (location = 0) in vec2 position;
 
+layout (location = 0) out vec2 fLocalPos;
+
 vec2 worldToScreen(vec2 worldPos);
 
 void main() {
-    vec2 worldPos = pc.position + pc.cameraPos + position * (pc.radius + pc.borderWidth);
+    // One extra pixel is kept around the shape for the smoothed edge
+    float pixel = 1. / max(sqrt(abs(determinant(pc.transform))), 1e-6);
+    vec2 localPos = position * (pc.radii + pc.borderOuter + pixel);
+    // The transform includes the position of the shape
+    vec2 worldPos = pc.transform * localPos + pc.translation;
     gl_Position = vec4(worldToScreen(worldPos), 0., 1.);
+    fLocalPos = localPos;
 }
 
 vec2 worldToScreen(vec2 worldPos) {
     return worldPos / pc.resolution * 2. - 1.;
-}
\ No newline at end of file
+}
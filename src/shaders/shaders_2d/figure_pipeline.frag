@@ -2,15 +2,17 @@
 
 layout(push_constant) uniform Constants {
     uvec2 resolution;
-    vec2 position;
-    vec4 borderColor;
-    vec2 size;
-    vec2 cameraPos;
-    uint borderWidth; 
+    vec2 translation;
+    mat2 transform;
 } pc;
 
+layout(set = 0, binding = 0) uniform sampler2D tex;
+
+layout(location = 0) in vec2 fUv;
+layout(location = 1) in vec4 fColor;
+
 layout(location = 0) out vec4 f_color;
 
 void main() {
-    f_color = vec4(1.);
+    f_color = texture(tex, fUv) * fColor;
 }
\ No newline at end of file
@@ -2,21 +2,25 @@
 
 layout(push_constant) uniform Constants {
     uvec2 resolution;
-    vec2 position;
-    vec4 borderColor;
-    vec2 size;
-    vec2 cameraPos;
-    uint borderWidth; 
+    vec2 translation;
+    mat2 transform;
 } pc;
 
 layout(location=0) in vec2 position;
+layout(location=1) in vec2 uvCoord;
+layout(location=2) in vec4 color;
+
+layout(location=0) out vec2 fUv;
+layout(location=1) out vec4 fColor;
 
 vec2 worldToScreen(vec2 worldPos);
 
 void main() {
-    vec2 worldPos = (pc.cameraPos + pc.position + position * pc.size);
+    vec2 worldPos = pc.transform * position + pc.translation;
     vec2 pos = worldToScreen(worldPos);
     gl_Position = vec4(pos, 0., 1.);
+    fUv = uvCoord;
+    fColor = color;
 }
 
 vec2 worldToScreen(vec2 worldPos) {
@@ -0,0 +1,50 @@
+// Gradient fills of the rect, circle and polygon pipelines
+
+#define TAU 6.28318530718
+#define MAX_STOPS 8
+
+layout(set = 0, binding = 0) uniform Fill {
+    vec4 colors[MAX_STOPS];
+    // Packed four offsets per vec4
+    vec4 offsets[MAX_STOPS / 4];
+    vec4 params;
+    // x: kind (0 solid, 1 linear, 2 radial, 3 conic), y: stop count
+    uvec4 info;
+} fill;
+
+// uv is relative to the bounding box of the shape, (0, 0) is the top left corner. Solid fills
+// use solidColor.
+vec4 fillColor(vec2 uv, vec4 solidColor) {
+    uint kind = fill.info.x;
+    if (kind == 0) {
+        return solidColor;
+    }
+
+    float t;
+    if (kind == 1) {
+        vec2 dir = fill.params.zw - fill.params.xy;
+        float lengthSquared = dot(dir, dir);
+        t = lengthSquared > 0. ? dot(uv - fill.params.xy, dir) / lengthSquared : 0.;
+    } else if (kind == 2) {
+        t = fill.params.z > 0. ? length(uv - fill.params.xy) / fill.params.z : 0.;
+    } else {
+        vec2 d = uv - fill.params.xy;
+        t = fract((atan(d.y, d.x) - fill.params.z) / TAU);
+    }
+    t = clamp(t, 0., 1.);
+
+    vec4 color = fill.colors[0];
+    for (uint i = 1; i < fill.info.y; i++) {
+        float previous = fill.offsets[(i - 1) / 4][(i - 1) % 4];
+        float next = fill.offsets[i / 4][i % 4];
+        if (t >= next) {
+            color = fill.colors[i];
+            continue;
+        }
+        if (t > previous) {
+            color = mix(fill.colors[i - 1], fill.colors[i], (t - previous) / (next - previous));
+        }
+        break;
+    }
+    return color;
+}
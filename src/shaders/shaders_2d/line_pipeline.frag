@@ -1,11 +1,15 @@
 #version 450
 
 layout(push_constant) uniform Constants {
-    vec4 color;
+    uvec2 resolution;
+    vec2 translation;
+    mat2 transform;
 } pc;
 
-layout(location=0) out vec4 f_color;
+layout(location = 0) in vec4 fColor;
+
+layout(location = 0) out vec4 f_color;
 
 void main() {
-    f_color = pc.color;
-}
\ No newline at end of file
+    f_color = fColor;
+}
@@ -1,11 +1,23 @@
 #version 450
 
 layout(push_constant) uniform Constants {
-    vec4 color;
+    uvec2 resolution;
+    vec2 translation;
+    mat2 transform;
 } pc;
 
-layout(location=1) in vec2 position;
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec4 color;
+
+layout(location = 0) out vec4 fColor;
+
+vec2 worldToScreen(vec2 worldPos);
 
 void main() {
-    gl_Position = vec4(position, 0., 1.);
-}
\ No newline at end of file
+    gl_Position = vec4(worldToScreen(pc.transform * position + pc.translation), 0., 1.);
+    fColor = color;
+}
+
+vec2 worldToScreen(vec2 worldPos) {
+    return worldPos / pc.resolution * 2. - 1.;
+}
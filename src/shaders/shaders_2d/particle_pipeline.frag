@@ -0,0 +1,30 @@
+#version 450
+
+#define SHAPE_CIRCLE 0
+
+layout(push_constant) uniform Constants {
+    uvec2 resolution;
+    vec2 translation;
+    mat2 transform;
+    uint shape;
+} pc;
+
+layout(set = 0, binding = 0) uniform sampler2D tex;
+
+layout(location = 0) in vec2 fUv;
+layout(location = 1) in vec2 fLocalPos;
+layout(location = 2) in vec4 fColor;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    vec4 color = texture(tex, fUv) * fColor;
+
+    if (pc.shape == SHAPE_CIRCLE) {
+        float dist = length(fLocalPos - 0.5);
+        float edge = fwidth(dist);
+        color.a *= 1. - smoothstep(0.5 - edge, 0.5, dist);
+    }
+
+    f_color = color;
+}
@@ -0,0 +1,43 @@
+#version 450
+
+layout(push_constant) uniform Constants {
+    uvec2 resolution;
+    vec2 translation;
+    mat2 transform;
+    uint shape;
+} pc;
+
+// Corner of the quad, shared by all particles
+layout(location=0) in vec2 position;
+
+// Per particle
+layout(location=1) in vec2 center;
+layout(location=2) in vec2 size;
+layout(location=3) in float rotation;
+layout(location=4) in vec4 color;
+// Min in xy, max in zw
+layout(location=5) in vec4 uvRect;
+
+layout(location=0) out vec2 fUv;
+layout(location=1) out vec2 fLocalPos;
+layout(location=2) out vec4 fColor;
+
+vec2 worldToScreen(vec2 worldPos);
+
+void main() {
+    float c = cos(rotation);
+    float s = sin(rotation);
+    vec2 localPos = center + mat2(c, s, -s, c) * (position * size);
+
+    vec2 worldPos = pc.transform * localPos + pc.translation;
+    vec2 pos = worldToScreen(worldPos);
+    gl_Position = vec4(pos, 0., 1.);
+
+    fLocalPos = position + 0.5;
+    fUv = mix(uvRect.xy, uvRect.zw, fLocalPos);
+    fColor = color;
+}
+
+vec2 worldToScreen(vec2 worldPos) {
+    return worldPos / pc.resolution * 2. - 1.;
+}
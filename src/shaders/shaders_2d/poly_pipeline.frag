@@ -6,12 +6,28 @@ layout(push_constant) uniform Constants {
     vec4 color;
     vec4 borderColor;
     vec2 size;
-    vec2 cameraPos;
+    vec2 translation;
+    mat2 transform;
     uint borderWidth; 
+    uint antialiasing;
 } pc;
 
+#include <fill.glsl>
+
+layout(location = 0) in vec2 fUv;
+layout(location = 1) in float fEdgeDistance;
+
 layout(location = 0) out vec4 f_color;
 
 void main() {
-    f_color = pc.color;
-}
\ No newline at end of file
+    float coverage = 1.;
+    if (pc.antialiasing != 0) {
+        // The fringe is one pixel wide, so its distance changes by about one per pixel
+        float edgeWidth = fwidth(fEdgeDistance);
+        coverage = edgeWidth > 0. ? clamp(1. - fEdgeDistance / edgeWidth, 0., 1.) : 1.;
+    } else if (fEdgeDistance > 0.) {
+        discard;
+    }
+    vec4 color = fillColor(fUv, pc.color);
+    f_color = vec4(color.rgb, color.a * coverage);
+}
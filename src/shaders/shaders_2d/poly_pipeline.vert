@@ -6,18 +6,28 @@ layout(push_constant) uniform Constants {
     vec4 color;
     vec4 borderColor;
     vec2 size;
-    vec2 cameraPos;
+    vec2 translation;
+    mat2 transform;
     float borderWidth; 
+    uint antialiasing;
 } pc;
 
 layout(location=0) in vec2 position;
+layout(location=1) in float edgeDistance;
+
+// Vertices are relative to the bounding box, which is given by position and size
+layout(location=0) out vec2 fUv;
+// Distance outside of the fill, zero inside and the fringe width on the outer fringe edge
+layout(location=1) out float fEdgeDistance;
 
 vec2 worldToScreen(vec2 worldPos);
 
 void main() {
-    vec2 worldPos = (pc.cameraPos + pc.position + position * pc.size);
+    vec2 worldPos = pc.transform * (pc.position + position * pc.size) + pc.translation;
     vec2 pos = worldToScreen(worldPos);
     gl_Position = vec4(pos, 0., 1.);
+    fUv = position;
+    fEdgeDistance = edgeDistance;
 }
 
 vec2 worldToScreen(vec2 worldPos) {
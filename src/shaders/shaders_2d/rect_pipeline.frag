@@ -0,0 +1,62 @@
+#version 450
+
+layout(push_constant) uniform Constants {
+    vec4 color;
+    vec4 borderColor;
+    vec4 cornerRadii;
+    mat2 transform;
+    uvec2 resolution;
+    vec2 size;
+    vec2 translation;
+    float borderWidth;
+    float borderOuter;
+    uint antialiasing;
+} pc;
+
+#include <fill.glsl>
+
+layout(location = 0) in vec2 fLocalPos;
+
+layout(location = 0) out vec4 f_color;
+
+float roundedBoxDistance(vec2 p, vec2 halfSize, vec4 radii);
+float coverage(float dist, float edgeWidth);
+
+void main() {
+    float dist = roundedBoxDistance(fLocalPos, pc.size * 0.5, pc.cornerRadii);
+    float edgeWidth = pc.antialiasing != 0 ? fwidth(dist) : 0.;
+
+    float shapeCoverage = coverage(dist - pc.borderOuter, edgeWidth);
+    if (shapeCoverage <= 0.) {
+        discard;
+    }
+
+    vec4 color = fillColor(fLocalPos / pc.size + 0.5, pc.color);
+    if (pc.borderWidth > 0.) {
+        float fillCoverage = coverage(dist - pc.borderOuter + pc.borderWidth, edgeWidth);
+        color = mix(pc.borderColor, color, fillCoverage);
+    }
+    f_color = vec4(color.rgb, color.a * shapeCoverage);
+}
+
+// Radii are ordered top left, top right, bottom right, bottom left (screen space, y down)
+float roundedBoxDistance(vec2 p, vec2 halfSize, vec4 radii) {
+    float r;
+    if (p.x < 0.) {
+        r = p.y < 0. ? radii.x : radii.w;
+    } else {
+        r = p.y < 0. ? radii.y : radii.z;
+    }
+
+    vec2 q = abs(p) - halfSize + r;
+    return min(max(q.x, q.y), 0.) + length(max(q, 0.)) - r;
+}
+
+// Part of the pixel inside of the edge at a signed distance of dist, edgeWidth is the change of
+// the distance over one pixel. Without a width the edge is hard.
+float coverage(float dist, float edgeWidth) {
+    if (edgeWidth <= 0.) {
+        return dist > 0. ? 0. : 1.;
+    }
+    return clamp(0.5 - dist / edgeWidth, 0., 1.);
+}
@@ -0,0 +1,36 @@
+#version 450
+
+layout(push_constant) uniform Constants {
+    vec4 color;
+    vec4 borderColor;
+    vec4 cornerRadii;
+    mat2 transform;
+    uvec2 resolution;
+    vec2 size;
+    vec2 translation;
+    float borderWidth;
+    float borderOuter;
+    uint antialiasing;
+} pc;
+
+layout(location = 0) in vec2 position;
+
+layout(location = 0) out vec2 fLocalPos;
+
+vec2 worldToScreen(vec2 worldPos);
+
+void main() {
+    // The quad is grown so the part of the border outside of the rect is drawn as well, with an
+    // extra pixel for the smoothed edge
+    float pixel = 1. / max(sqrt(abs(determinant(pc.transform))), 1e-6);
+    vec2 localPos = position * (pc.size + 2. * (pc.borderOuter + pixel));
+
+    // The transform includes the position, rotation and anchor of the rect
+    vec2 worldPos = pc.transform * localPos + pc.translation;
+    gl_Position = vec4(worldToScreen(worldPos), 0., 1.);
+    fLocalPos = localPos;
+}
+
+vec2 worldToScreen(vec2 worldPos) {
+    return worldPos / pc.resolution * 2. - 1.;
+}
@@ -0,0 +1,60 @@
+#version 450
+
+layout(push_constant) uniform Constants {
+    vec4 color;
+    vec4 cornerRadii;
+    mat2 transform;
+    uvec2 resolution;
+    vec2 size;
+    vec2 translation;
+    float blur;
+    float spread;
+} pc;
+
+layout(location = 0) in vec2 fLocalPos;
+
+layout(location = 0) out vec4 f_color;
+
+float roundedBoxDistance(vec2 p, vec2 halfSize, vec4 radii);
+float erf(float x);
+
+void main() {
+    // Spread grows the box and its corner radii alike
+    vec2 halfSize = max(pc.size * 0.5 + pc.spread, 0.);
+    vec4 radii = clamp(pc.cornerRadii + pc.spread, 0., min(halfSize.x, halfSize.y));
+    float dist = roundedBoxDistance(fLocalPos, halfSize, radii);
+
+    // The coverage of a gaussian blurred edge, which is exact for straight edges. Like in CSS the
+    // blur is twice the standard deviation.
+    float alpha;
+    if (pc.blur > 0.) {
+        float sigma = pc.blur * 0.5;
+        alpha = 0.5 - 0.5 * erf(dist / (sigma * sqrt(2.)));
+    } else {
+        alpha = clamp(0.5 - dist, 0., 1.);
+    }
+
+    f_color = vec4(pc.color.rgb, pc.color.a * alpha);
+}
+
+// Radii are ordered top left, top right, bottom right, bottom left (screen space, y down)
+float roundedBoxDistance(vec2 p, vec2 halfSize, vec4 radii) {
+    float r;
+    if (p.x < 0.) {
+        r = p.y < 0. ? radii.x : radii.w;
+    } else {
+        r = p.y < 0. ? radii.y : radii.z;
+    }
+
+    vec2 q = abs(p) - halfSize + r;
+    return min(max(q.x, q.y), 0.) + length(max(q, 0.)) - r;
+}
+
+// Approximation of the error function from Abramowitz and Stegun
+float erf(float x) {
+    float s = sign(x);
+    float a = abs(x);
+    float t = 1. + (0.278393 + (0.230389 + 0.078108 * a * a) * a) * a;
+    t *= t;
+    return s - s / (t * t);
+}
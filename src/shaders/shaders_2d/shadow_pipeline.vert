@@ -0,0 +1,33 @@
+#version 450
+
+layout(push_constant) uniform Constants {
+    vec4 color;
+    vec4 cornerRadii;
+    mat2 transform;
+    uvec2 resolution;
+    vec2 size;
+    vec2 translation;
+    float blur;
+    float spread;
+} pc;
+
+layout(location = 0) in vec2 position;
+
+layout(location = 0) out vec2 fLocalPos;
+
+vec2 worldToScreen(vec2 worldPos);
+
+void main() {
+    // The quad is grown so the blurred edge fits, the blur fades out within 3 sigma
+    float margin = max(pc.spread, 0.) + pc.blur * 1.5 + 1.;
+    vec2 localPos = position * (pc.size + 2. * margin);
+
+    // The transform includes the position, rotation and offset of the shadow
+    vec2 worldPos = pc.transform * localPos + pc.translation;
+    gl_Position = vec4(worldToScreen(worldPos), 0., 1.);
+    fLocalPos = localPos;
+}
+
+vec2 worldToScreen(vec2 worldPos) {
+    return worldPos / pc.resolution * 2. - 1.;
+}
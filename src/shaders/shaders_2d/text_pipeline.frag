@@ -16,28 +16,74 @@
 
 #version 450
 
+#define MAX_SAMPLE_STEPS 6
+
 layout(push_constant) uniform Constants {
     uvec2 resolution;
     vec2 position;
     vec4 color;
-    vec2 cameraPos;
+    vec2 translation;
+    mat2 transform;
+    // Only set for shadows, in pixels
+    float blur;
+    float spread;
 } pc;
 
 layout (location = 0) in vec2 fUvCoord;
+// Texels of the glyph in the atlas, from the top left up to the bottom right
+layout (location = 1) flat in vec4 fGlyphRect;
 
 layout (location = 0) out vec4 f_color;
 
 layout (set = 0, binding = 0) uniform usampler2D charTex;
 
+float coverage(vec2 texelPos);
+
 void main() {
-    uint sampled = texture(charTex, fUvCoord).r;
+    if (pc.blur > 0. || pc.spread > 0.) {
+        // Shadows average the glyph over a gaussian kernel. Samples within spread count fully,
+        // which roughly grows the glyph by spread before it is blurred.
+        float radius = pc.spread + pc.blur * 1.5;
+        int steps = clamp(int(ceil(radius)), 1, MAX_SAMPLE_STEPS);
+        float stepSize = radius / float(steps);
+        float sigma = max(pc.blur * 0.5, 0.5);
+
+        float sum = 0.;
+        float weights = 0.;
+        for (int y = -steps; y <= steps; y++) {
+            for (int x = -steps; x <= steps; x++) {
+                vec2 offset = vec2(x, y) * stepSize;
+                float distance = length(offset);
+                float spreadDistance = max(distance - pc.spread, 0.);
+                sum += coverage(fUvCoord + offset)
+                    * exp(-spreadDistance * spreadDistance / (2. * sigma * sigma));
+                weights += exp(-distance * distance / (2. * sigma * sigma));
+            }
+        }
+
+        f_color = vec4(pc.color.rgb, pc.color.a * clamp(sum / weights, 0., 1.));
+        return;
+    }
+
+    float sampled = coverage(fUvCoord);
 
     // TODO: Check if it is better with alpha
     float alpha = 0.;
-    if (sampled > 0) {
+    if (sampled > 0.) {
         alpha = 1.;
     }
 
-    vec4 charValue = vec4(vec3(sampled / 255.), alpha);
+    vec4 charValue = vec4(vec3(sampled), alpha);
     f_color = pc.color * charValue;
-}
\ No newline at end of file
+}
+
+// Texels outside of the glyph are empty, the quads of shadows reach past the glyph into its
+// neighbours in the atlas
+float coverage(vec2 texelPos) {
+    ivec2 texel = ivec2(floor(texelPos));
+    if (any(lessThan(texel, ivec2(fGlyphRect.xy)))
+        || any(greaterThanEqual(texel, ivec2(fGlyphRect.zw)))) {
+        return 0.;
+    }
+    return float(texelFetch(charTex, texel, 0).r) / 255.;
+}
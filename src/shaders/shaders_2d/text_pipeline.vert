@@ -4,20 +4,28 @@ layout(push_constant) uniform Constants {
     uvec2 resolution;
     vec2 position;
     vec4 color;
-    vec2 cameraPos;
+    vec2 translation;
+    mat2 transform;
+    // Only set for shadows, in pixels
+    float blur;
+    float spread;
 } pc;
 
 layout(location = 0) in vec2 position;
 layout(location = 1) in vec2 uvCoord;
+layout(location = 2) in vec4 glyphRect;
 
+// Both in texels of the glyph atlas
 layout(location = 0) out vec2 fUvCoord;
+layout(location = 1) flat out vec4 fGlyphRect;
 
 vec2 worldToScreen(vec2 worldPos);
 
 void main() {
-    vec2 pos = worldToScreen(position + pc.position + pc.cameraPos);
+    vec2 pos = worldToScreen(pc.transform * (position + pc.position) + pc.translation);
     gl_Position = vec4(pos, 0., 1.);
     fUvCoord = uvCoord;
+    fGlyphRect = glyphRect;
 }
 
 vec2 worldToScreen(vec2 worldPos) {